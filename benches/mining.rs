@@ -0,0 +1,38 @@
+//! Compares the serial proof of work with the parallel one, run with `cargo bench --features parallel`.
+
+use std::{thread, time::{Duration, Instant}};
+use blockchain::block::{Block, DIFFICULTY};
+
+/// The number of blocks mined by each miner.
+const BLOCKS: usize = 8;
+
+/// This function mines `BLOCKS` blocks with the miner, and returns the average time spent on each block.
+fn bench(name: &str, mine: impl Fn(usize, [u8; 64]) -> Block) -> Duration {
+    let mut prev_hash = Block::default().hash();
+    let start = Instant::now();
+
+    for index in 1..=BLOCKS {
+        let block = mine(index, prev_hash);
+
+        assert!(block.verify_nonce(DIFFICULTY));
+
+        prev_hash = block.hash();
+    }
+
+    let average = start.elapsed() / BLOCKS as u32;
+
+    println!("{:<12} {:>10.2?} per block", name, average);
+
+    average
+}
+
+fn main() {
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+
+    let serial = bench("serial", |index, prev_hash| Block::try_new(index, prev_hash, Vec::new(), DIFFICULTY, None).unwrap());
+    let parallel = bench(&format!("parallel ({})", workers), |index, prev_hash| {
+        Block::try_new_parallel(index, prev_hash, Vec::new(), DIFFICULTY, workers).unwrap()
+    });
+
+    println!("speedup      {:>10.2}x", serial.as_secs_f64() / parallel.as_secs_f64());
+}
@@ -1,23 +1,50 @@
 use sha2::{Sha512, Digest};
+#[cfg(not(feature = "argon2"))]
 use std::convert::TryInto;
 use rand::rngs::OsRng;
-use std::fmt;
-use ed25519_dalek::Keypair;
-use crate::positive_f64::PositiveF64;
+use std::{fmt, error, ptr};
+use std::sync::atomic::{self, Ordering};
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+#[cfg(feature = "argon2")]
+use argon2::Argon2;
+use crate::{
+    positive_f64::{PositiveF64, InvalidNumber},
+    transaction::{Transaction, TransactionBuilder, ValidationError},
+};
+#[cfg(feature = "keystore")]
+use crate::encoding::{Writer, Reader};
+
+/// The fee paid by the transaction of `Account::sweep_into()`, which is taken from the balance moved.
+pub const SWEEP_FEE: f64 = 0.01;
 
 /// A structure to handle accounts for the currency.
 /// 
 /// Every account has a first name, a last name, a balance (set to 0.0) and a password,
-/// which is used to validate the transactions; the password is saved using the SHA-512 hashing algorithm.
+/// which is used to validate the transactions; the password is saved using the SHA-512 hashing algorithm,
+/// or using Argon2id, salted with a random salt stored in the account, if the `argon2` feature is enabled.
 /// Also, every account has a `Keypair` which is used to validate the signature of the transaction,
 /// using the `ed25519_dalek` crate.
+/// 
+/// An account can have an overdraft limit (set to 0.0), which allows its balance to go down to `-overdraft_limit`:
+/// the money owed is kept in the `debt`, which is paid back before the money is added to the `balance`.
+/// 
+/// Every account has a nonce (set to 0), which is incremented every time the account sends a transaction,
+/// so that otherwise identical transactions have different hashes, and old transactions can't be replayed.
+/// 
+/// When an account is dropped, its keypair and the hash of its password are overwritten with zeros,
+/// so that they don't linger in the freed memory.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Account {
     first_name: String,
     last_name: String,
     balance: PositiveF64,
+    debt: PositiveF64,
+    overdraft_limit: f64,
+    nonce: u64,
     keypair: [u8; 64],
     hash_password: [u8; 64],
+    #[cfg(feature = "argon2")]
+    salt: [u8; 16],
 }
 
 impl Account {
@@ -34,79 +61,237 @@ impl Account {
         let mut csprng = OsRng;
         let keypair: Keypair = Keypair::generate(&mut csprng);
 
-        let mut hasher = Sha512::new();
+        #[cfg(feature = "argon2")]
+        let salt = {
+            let mut salt = [0; 16];
 
-        hasher.update(password.as_bytes());
+            rand::RngCore::fill_bytes(&mut csprng, &mut salt);
 
-        let hash_password = hasher
-            .finalize()[..]
-            .try_into()
-            .expect("Error generating the SHA-512 hash of the password.");
+            salt
+        };
 
-        Self {
+        let mut account = Self {
             first_name: String::from(first_name),
             last_name: String::from(last_name),
-            balance: PositiveF64::new(0.0).unwrap(),
+            balance: PositiveF64::zero(),
+            debt: PositiveF64::zero(),
+            overdraft_limit: 0.0,
+            nonce: 0,
             keypair: keypair.to_bytes(),
-            hash_password,
-        }
+            hash_password: [0; 64],
+            #[cfg(feature = "argon2")]
+            salt,
+        };
+
+        account.hash_password = account.hash_of(password);
+
+        account
     }
 
-    /// A method to add money to your balance; the amount can't be `0.0`, and can't be negative.
+    /// Generates a new `Account`, whose balance is set to the initial amount.
+    /// 
+    /// The function returns a `Result<Account, InvalidNumber>`, because
+    /// if the initial amount is negative, an `InvalidNumber::NegativeValue` error is returned.
     /// 
     /// # Example
     /// ```
     /// # use blockchain::account::Account;
+    /// let nyssa = Account::new_with_balance("Nyssa", "Traken", "Nyssa_of_Traken", 100.0).unwrap();
+    /// 
+    /// assert_eq!(nyssa.balance(), 100.0);
+    /// 
+    /// assert!(Account::new_with_balance("Nyssa", "Traken", "Nyssa_of_Traken", -100.0).is_err());
+    /// ```
+    pub fn new_with_balance(first_name: &str, last_name: &str, password: &str, initial: f64) -> Result<Account, InvalidNumber> {
+        let balance = PositiveF64::new(initial)?;
+
+        let mut account = Account::new(first_name, last_name, password);
+
+        account.balance = balance;
+
+        Ok(account)
+    }
+
+    /// A method to add money to your balance; the amount can't be `0.0`, and can't be negative.
+    /// 
+    /// The function returns a `Result<(), MoneyError>`, because
+    /// if the amount is `0.0`, a `MoneyError::ZeroAmount` error is returned,
+    /// if the amount is negative, a `MoneyError::Negative` error is returned,
+    /// and if the amount is NaN or infinite, or the balance would overflow, a `MoneyError::NotFinite` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::{Account, MoneyError};
     /// let mut allen = Account::new("Allen", "Johnson", "AllenJ500321#");
-    /// allen.add_money(100.0);
+    /// allen.add_money(100.0).unwrap();
     /// 
     /// assert_eq!(allen.balance(), 100.0);
+    /// 
+    /// assert_eq!(allen.add_money(0.0), Err(MoneyError::ZeroAmount));
+    /// assert_eq!(allen.add_money(-5.0), Err(MoneyError::Negative));
+    /// assert_eq!(allen.balance(), 100.0); // the balance doesn't change if the amount isn't valid
     /// ```
-    #[allow(dead_code)]
-    pub fn add_money(&mut self, amount: f64) {
+    pub fn add_money(&mut self, amount: f64) -> Result<(), MoneyError> {
         if amount == 0.0 {
-            eprintln!("Can't add a zero-value amount to the balance.")
+            Err(MoneyError::ZeroAmount)
         } else {
-            match PositiveF64::new(amount) {
-                Ok(a) => self.balance += a,
-                Err(e) => eprintln!("{} Details: can't add a negative amount to the balance.", e),
-            }
+            self.credit(PositiveF64::new(amount)?)?;
+
+            Ok(())
         }
     }
 
-
     /// A method to subtract money to your balance; the amount to subtract can't be `0.0`, can't be negative,
-    /// and can't be more than the amount in your balance.
+    /// and can't be more than the amount in your balance plus the overdraft still available.
+    /// 
+    /// The function returns a `Result<(), MoneyError>`, because
+    /// if the amount is `0.0`, a `MoneyError::ZeroAmount` error is returned,
+    /// if the amount is negative, a `MoneyError::Negative` error is returned,
+    /// if the amount is NaN or infinite, a `MoneyError::NotFinite` error is returned,
+    /// and if the amount is more than the balance plus the available overdraft,
+    /// a `MoneyError::InsufficientFunds` error is returned.
     /// 
     /// # Example
     /// ```
-    /// # use blockchain::account::Account;
+    /// # use blockchain::account::{Account, MoneyError};
     /// let mut branda = Account::new("Branda", "Pickle", "brandA;picklE;+1992");
-    /// branda.add_money(50.0); // you must have more than 0.0 in your balance
+    /// branda.add_money(50.0).unwrap(); // you must have more than 0.0 in your balance
     /// 
-    /// branda.sub_money(20.0);
+    /// branda.sub_money(20.0).unwrap();
     /// 
     /// assert_eq!(branda.balance(), 30.0); // 50.0 - 30.0 = 20.0
+    /// 
+    /// assert_eq!(branda.sub_money(0.0), Err(MoneyError::ZeroAmount));
+    /// assert_eq!(branda.sub_money(-5.0), Err(MoneyError::Negative));
+    /// assert_eq!(branda.sub_money(40.0), Err(MoneyError::InsufficientFunds));
+    /// assert_eq!(branda.balance(), 30.0); // the balance doesn't change if the subtraction fails
     /// ```
-    #[allow(dead_code)]
-    pub fn sub_money(&mut self, amount: f64) {
+    pub fn sub_money(&mut self, amount: f64) -> Result<(), MoneyError> {
         if amount == 0.0 {
-            eprintln!("Can't subtract a zero-value amount to the balance.")
-        } else {
-            match PositiveF64::new(amount) {
-                Ok(a) => {
-                    if PositiveF64::new(self.balance.value() - a.value()).is_ok() { // if the difference is >= 0.0
-                        self.balance -= a
-                    } else {
-                        eprintln!("Can't subtract an amount that is more than the amount in your balance.")
-                    }
-                },
-                Err(e) => eprintln!("{} Details: can't subtract a negative amount to the balance.", e)
-            }
+            return Err(MoneyError::ZeroAmount);
+        }
+
+        let amount = PositiveF64::new(amount)?;
+
+        match self.balance.checked_sub(amount) {
+            Ok(balance) => self.balance = balance,
+            Err(_) => {
+                // the amount is more than the balance, and a debt that overflows is more than any overdraft
+                let debt = (amount - self.balance)
+                    .checked_add(self.debt)
+                    .map_err(|_| MoneyError::InsufficientFunds)?;
+
+                if debt.value() > self.overdraft_limit {
+                    return Err(MoneyError::InsufficientFunds);
+                }
+
+                self.balance = PositiveF64::zero();
+                self.debt = debt;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// A method to transfer money from your balance to the balance of another account;
+    /// the amount is subtracted from your balance as in `sub_money`, so the same errors are returned.
+    /// 
+    /// If the subtraction fails, or the balance of the other account would overflow,
+    /// neither of the two accounts is changed.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::{Account, MoneyError};
+    /// let mut donna = Account::new("Donna", "Noble", "Chiswick_Temp_2008");
+    /// let mut wilfred = Account::new("Wilfred", "Mott", "stargazer_wilf");
+    /// donna.add_money(30.0).unwrap();
+    /// 
+    /// donna.transfer(&mut wilfred, 20.0).unwrap();
+    /// 
+    /// assert_eq!(donna.balance(), 10.0);
+    /// assert_eq!(wilfred.balance(), 20.0);
+    /// 
+    /// assert_eq!(donna.transfer(&mut wilfred, 15.0), Err(MoneyError::InsufficientFunds));
+    /// assert_eq!(donna.balance(), 10.0); // no partial transfer
+    /// assert_eq!(wilfred.balance(), 20.0);
+    /// ```
+    pub fn transfer(&mut self, to: &mut Account, amount: f64) -> Result<(), MoneyError> {
+        self.sub_money(amount)?;
+
+        if let Err(e) = to.add_money(amount) {
+            // the amount was just subtracted, so adding it back can't fail
+            self.add_money(amount).unwrap();
+
+            return Err(e);
         }
+
+        Ok(())
     }
 
-    /// This method returns the balance of the account, since the `balance` field isn't `pub`.
+    /// A method to move the whole balance of your account into another account,
+    /// which returns the `Transaction` of the transfer; the password of your account is required.
+    /// 
+    /// The fee of the transaction, `SWEEP_FEE`, is paid from the balance, so the amount moved is the balance minus the fee,
+    /// and your account is left empty.
+    /// 
+    /// The function returns a `Result<Transaction, SweepError>`, because
+    /// if the password isn't correct, a `SweepError::WrongPassword` error is returned,
+    /// if your balance doesn't exceed the fee, so there is no money to move, a `SweepError::EmptyBalance` error is returned,
+    /// and if the transaction can't be built, a `SweepError::InvalidTransaction` error is returned,
+    /// with the `ValidationError` of the transaction; if an error is returned, neither account is changed.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::{Account, SweepError, SWEEP_FEE};
+    /// let mut old_wallet = Account::new_with_balance("Sarah", "Smith", "K9_is_a_good_dog", 35.0).unwrap();
+    /// let mut new_wallet = Account::new_with_balance("Sarah", "Smith", "K9_Mark_III", 5.0).unwrap();
+    /// 
+    /// assert_eq!(old_wallet.sweep_into(&mut new_wallet, "wrong_password").unwrap_err(), SweepError::WrongPassword);
+    /// 
+    /// let transaction = old_wallet.sweep_into(&mut new_wallet, "K9_is_a_good_dog").unwrap();
+    /// 
+    /// assert_eq!(transaction.amount(), 35.0 - SWEEP_FEE);
+    /// assert_eq!(transaction.fee(), SWEEP_FEE);
+    /// assert_eq!(old_wallet.balance(), 0.0);
+    /// assert_eq!(new_wallet.balance(), 40.0 - SWEEP_FEE);
+    /// 
+    /// assert_eq!(old_wallet.sweep_into(&mut new_wallet, "K9_is_a_good_dog").unwrap_err(), SweepError::EmptyBalance);
+    /// ```
+    pub fn sweep_into(&mut self, dest: &mut Account, password: &str) -> Result<Transaction, SweepError> {
+        if !self.verify_password(password) {
+            return Err(SweepError::WrongPassword);
+        }
+
+        let balance = self.balance();
+
+        if balance <= SWEEP_FEE {
+            return Err(SweepError::EmptyBalance);
+        }
+
+        let amount = balance - SWEEP_FEE;
+
+        let transaction = TransactionBuilder::new(self.clone(), dest.clone(), amount, password)
+            .fee(SWEEP_FEE)
+            .build()
+            .map_err(SweepError::InvalidTransaction)?;
+
+        // the amount and the fee make up the whole balance
+        self.sub_money(balance).map_err(SweepError::Money)?;
+
+        if let Err(e) = dest.add_money(amount) {
+            // the balance was just subtracted, so adding it back can't fail
+            self.add_money(balance).unwrap();
+
+            return Err(SweepError::Money(e));
+        }
+
+        self.increment_nonce();
+
+        Ok(transaction)
+    }
+
+    /// This method returns the balance of the account, since the `balance` field isn't `pub`;
+    /// the balance is negative if the account is using its overdraft.
     /// 
     /// # Example
     /// ```
@@ -115,12 +300,112 @@ impl Account {
     /// 
     /// assert_eq!(walter.balance(), 0.0); // your balance is 0.0 when the account is created
     /// 
-    /// walter.add_money(50.0);
+    /// walter.add_money(50.0).unwrap();
     /// 
     /// assert_eq!(walter.balance(), 50.0);
     /// ```
     pub fn balance(&self) -> f64 {
-        self.balance.value()
+        self.balance.value() - self.debt.value()
+    }
+
+    /// This method returns the balance of the account as a `PositiveF64`, without validating it again;
+    /// if the account is using its overdraft, the balance returned is `0.0`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let mut clara = Account::new("Clara", "Oswald", "Run_You_Clever_Boy");
+    /// clara.add_money(25.0).unwrap();
+    /// 
+    /// assert_eq!(clara.balance_positive().value(), clara.balance());
+    /// ```
+    pub fn balance_positive(&self) -> PositiveF64 {
+        self.balance
+    }
+
+    /// This method returns the overdraft limit of the account, since the `overdraft_limit` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let amelia = Account::new("Amelia", "Pond", "Fish_Fingers&Custard");
+    /// 
+    /// assert_eq!(amelia.overdraft_limit(), 0.0); // there's no overdraft when the account is created
+    /// ```
+    pub fn overdraft_limit(&self) -> f64 {
+        self.overdraft_limit
+    }
+
+    /// This method returns the nonce of the account, which is the number of transactions it has sent,
+    /// since the `nonce` field isn't `pub`; the next transaction sent by the account carries this nonce.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut clara = Account::new("Clara", "Oswald", "clara_oswald_1866");
+    /// let mut danny = Account::new("Danny", "Pink", "D4nny_P1nk!");
+    /// clara.add_money(10.0).unwrap();
+    /// 
+    /// assert_eq!(clara.nonce(), 0);
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(clara.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut clara, &mut danny, 4.0, "clara_oswald_1866").unwrap();
+    /// 
+    /// assert_eq!(clara.nonce(), 1);
+    /// assert_eq!(danny.nonce(), 0); // receiving money doesn't change the nonce
+    /// ```
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// This method is called when the account sends a transaction, so that the next one carries a new nonce.
+    pub(crate) fn increment_nonce(&mut self) {
+        self.nonce += 1;
+    }
+
+    /// This method sets the overdraft limit of the account, so that the balance can go down to `-overdraft_limit`.
+    /// 
+    /// The function returns a `Result<(), InvalidNumber>`, because
+    /// if the limit is negative, an `InvalidNumber::NegativeValue` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let mut rory = Account::new("Rory", "Williams", "the_last_centurion");
+    /// rory.add_money(10.0).unwrap();
+    /// rory.set_overdraft_limit(50.0).unwrap();
+    /// 
+    /// rory.sub_money(40.0).unwrap(); // within the overdraft
+    /// 
+    /// assert_eq!(rory.balance(), -30.0);
+    /// 
+    /// assert!(rory.sub_money(25.0).is_err()); // beyond the overdraft, the balance doesn't change
+    /// 
+    /// assert_eq!(rory.balance(), -30.0);
+    /// 
+    /// rory.add_money(50.0).unwrap(); // the debt is paid back first
+    /// 
+    /// assert_eq!(rory.balance(), 20.0);
+    /// ```
+    pub fn set_overdraft_limit(&mut self, overdraft_limit: f64) -> Result<(), InvalidNumber> {
+        self.overdraft_limit = PositiveF64::new(overdraft_limit)?.value();
+
+        Ok(())
+    }
+
+    /// This method adds the amount to the account, paying back the debt first;
+    /// if the balance would overflow, the account isn't changed and an `InvalidNumber::NotFinite` error is returned.
+    fn credit(&mut self, amount: PositiveF64) -> Result<(), InvalidNumber> {
+        let repaid = amount.min(self.debt);
+
+        self.balance = self.balance.checked_add(amount - repaid)?;
+        self.debt -= repaid;
+
+        Ok(())
     }
 
     /// This method returns the keypair of the account, since the `keypair` field isn't `pub`.
@@ -136,7 +421,97 @@ impl Account {
         self.keypair
     }
 
-    /// This method returns the hash of the password of the account, since the `hash_password` field isn't `pub`.
+    /// This method returns the public key of the account, which is the public half of its keypair,
+    /// so that signatures can be verified without the secret key.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let graham = Account::new("Graham", "O'Brien", "Graham_OB_Bus_Driver");
+    /// 
+    /// assert_eq!(graham.public_key().to_bytes().len(), 32);
+    /// assert_eq!(graham.public_key().to_bytes(), graham.keypair()[32..]);
+    /// ```
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_bytes(&self.keypair[32..]).expect("Error generating the PublicKey of the account.")
+    }
+
+    /// This method signs the message with the keypair of the account.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let clara = Account::new("Clara", "Oswald", "Run_You_Clever_Boy");
+    /// 
+    /// let signature = clara.sign(b"Souffle girl");
+    /// 
+    /// assert!(Account::verify(&clara.public_key(), b"Souffle girl", &signature));
+    /// ```
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let keypair = Keypair::from_bytes(&self.keypair).expect("Error generating the Keypair while signing the message.");
+
+        keypair.sign(message)
+    }
+
+    /// This function checks if the signature of the message was made with the secret key of the public key.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let clara = Account::new("Clara", "Oswald", "Run_You_Clever_Boy");
+    /// let danny = Account::new("Danny", "Pink", "D4nny_P1nk!");
+    /// 
+    /// let signature = clara.sign(b"Souffle girl");
+    /// 
+    /// assert!(!Account::verify(&clara.public_key(), b"Impossible girl", &signature)); // the message is different
+    /// assert!(!Account::verify(&danny.public_key(), b"Souffle girl", &signature)); // the signer is different
+    /// ```
+    pub fn verify(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+        public_key.verify(message, signature).is_ok()
+    }
+
+    /// This method returns the address of the account, which is the hex encoding
+    /// of the first 20 bytes of the SHA-512 hash of its public key.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let gloria = Account::new("Gloria", "Hale", "Gl0ria_Hale_Pass!");
+    /// let hector = Account::new("Hector", "Hale", "Gl0ria_Hale_Pass!");
+    /// 
+    /// assert_eq!(gloria.address(), gloria.clone().address()); // the address doesn't change across clones
+    /// assert_ne!(gloria.address(), hector.address()); // every new account has its own address
+    /// assert_eq!(gloria.address().len(), 40);
+    /// ```
+    pub fn address(&self) -> String {
+        let mut hasher = Sha512::new();
+
+        hasher.update(&self.keypair[32..]);
+
+        hasher
+            .finalize()[..20]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// This method formats the account as `first last [address]: balance`, showing only the first 8 hex digits
+    /// of the address, so that accounts with the same name can be told apart; no secret is ever shown.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let mut adric = Account::new("Adric", "Alzarius", "Mathematical_Excellence");
+    /// adric.add_money(12.5).unwrap();
+    /// 
+    /// assert_eq!(adric.display_verbose(), format!("Adric Alzarius [{}]: 12.5", &adric.address()[..8]));
+    /// ```
+    pub fn display_verbose(&self) -> String {
+        format!("{} {} [{}]: {}", self.first_name, self.last_name, &self.address()[..8], self.balance())
+    }
+
+    /// This method returns the hash of the password of the account, since the `hash_password` field isn't `pub`;
+    /// with the `argon2` feature, the hash is salted, so it's different for every account.
     /// 
     /// # Example
     /// ```
@@ -144,12 +519,88 @@ impl Account {
     /// # use hex_literal::hex;
     /// let denzel = Account::new("Denzel", "Pratt", "My_Secret_Password@@@__789");
     /// 
+    /// # #[cfg(not(feature = "argon2"))]
     /// assert_eq!(denzel.hash_password(), hex!("de4b5227910fb4c8fa8a7702dc25807a4c6d50090615b0cd5a52446438b461071d3be479d2710ae65d48cb9fc30a3a7775f5b97a6d5b4692d17c73ab6dfd461f"));
     /// ```
     pub fn hash_password(&self) -> [u8; 64] {
         self.hash_password
     }
 
+    /// This method checks if the candidate password is the password of the account;
+    /// the hashes are compared in constant time, so that no information is leaked by the time spent.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let yasmin = Account::new("Yasmin", "Khan", "Yaz_Khan_Sheffield");
+    /// 
+    /// assert!(yasmin.verify_password("Yaz_Khan_Sheffield"));
+    /// assert!(!yasmin.verify_password("yaz_khan_sheffield"));
+    /// ```
+    pub fn verify_password(&self, candidate: &str) -> bool {
+        self.verify_password_hash(&self.hash_of(candidate))
+    }
+
+    /// This method replaces the password of the account with the new one, only if the old password is correct.
+    /// 
+    /// The function returns a `Result<(), ValidationError>`, because
+    /// if the old password isn't correct, a `ValidationError::WrongPassword` error is returned,
+    /// and the password isn't changed.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// # use blockchain::transaction::ValidationError;
+    /// let mut ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// 
+    /// assert_eq!(ryan.change_password("wrong_password", "new_password"), Err(ValidationError::WrongPassword));
+    /// assert!(ryan.verify_password("ryan_sinclair_2018")); // the password didn't change
+    /// 
+    /// ryan.change_password("ryan_sinclair_2018", "Ryan_S!nclair_2019").unwrap();
+    /// 
+    /// assert!(ryan.verify_password("Ryan_S!nclair_2019"));
+    /// assert!(!ryan.verify_password("ryan_sinclair_2018"));
+    /// ```
+    pub fn change_password(&mut self, old: &str, new: &str) -> Result<(), ValidationError> {
+        if !self.verify_password(old) {
+            return Err(ValidationError::WrongPassword);
+        }
+
+        self.hash_password = self.hash_of(new);
+
+        Ok(())
+    }
+
+    /// This method compares the hash with the hash of the password of the account in constant time.
+    pub(crate) fn verify_password_hash(&self, hash: &[u8; 64]) -> bool {
+        constant_time_eq(&self.hash_password, hash)
+    }
+
+    /// This method computes the SHA-512 hash of the password.
+    #[cfg(not(feature = "argon2"))]
+    pub(crate) fn hash_of(&self, password: &str) -> [u8; 64] {
+        let mut hasher = Sha512::new();
+
+        hasher.update(password.as_bytes());
+
+        hasher
+            .finalize()[..]
+            .try_into()
+            .expect("Error generating the SHA-512 hash of the password.")
+    }
+
+    /// This method computes the Argon2id hash of the password, salted with the salt of the account.
+    #[cfg(feature = "argon2")]
+    pub(crate) fn hash_of(&self, password: &str) -> [u8; 64] {
+        let mut hash = [0; 64];
+
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), &self.salt, &mut hash)
+            .expect("Error generating the Argon2id hash of the password.");
+
+        hash
+    }
+
     /// Adds money to an account without checking the input.
     /// 
     /// # Safety
@@ -181,7 +632,7 @@ impl Account {
     /// }
     /// ```
     pub unsafe fn add_money_unchecked(&mut self, amount: f64) {
-        self.balance += PositiveF64::new_unchecked(amount)
+        self.credit(PositiveF64::new_unchecked(amount)).unwrap()
     }
 
     /// Subtracts money from an account without checking the input.
@@ -194,7 +645,7 @@ impl Account {
     /// # use blockchain::account::Account;
     /// unsafe {
     ///     let mut mary = Account::new("Mary", "Shelley", "marymaryMoo123#");
-    ///     mary.add_money(10.0); // you must have more than 0.0 in your balance
+    ///     mary.add_money(10.0).unwrap(); // you must have more than 0.0 in your balance
     /// 
     ///     mary.sub_money_unchecked(8.0);
     /// 
@@ -218,10 +669,229 @@ impl Account {
     pub unsafe fn sub_money_unchecked(&mut self, amount: f64) {
         self.balance -= PositiveF64::new_unchecked(amount)
     }
+
+    /// This method returns the public part of the account, which is what a `Transaction` records of its parties:
+    /// only the public key and the nonce are kept, while the names, the balance, the secret key
+    /// and the hash of the password are left empty.
+    pub(crate) fn public_part(&self) -> Self {
+        Account::from_public_key(&self.public_key(), self.nonce)
+    }
+
+    /// Generates the public part of an account, as in `.public_part()`, from its public key and its nonce.
+    pub(crate) fn from_public_key(public_key: &PublicKey, nonce: u64) -> Self {
+        let mut keypair = [0; 64];
+
+        keypair[32..].copy_from_slice(public_key.as_bytes());
+
+        Self {
+            first_name: String::new(),
+            last_name: String::new(),
+            balance: PositiveF64::zero(),
+            debt: PositiveF64::zero(),
+            overdraft_limit: 0.0,
+            nonce,
+            keypair,
+            hash_password: [0; 64],
+            #[cfg(feature = "argon2")]
+            salt: [0; 16],
+        }
+    }
+
+    /// This method writes the fields of the account as bytes, secrets included, so that it can be
+    /// encrypted in a keystore; transactions only write the public part of their accounts.
+    #[cfg(feature = "keystore")]
+    pub(crate) fn encode(&self, writer: &mut Writer) {
+        writer.string(&self.first_name);
+        writer.string(&self.last_name);
+        writer.f64(self.balance.value());
+        writer.f64(self.debt.value());
+        writer.f64(self.overdraft_limit);
+        writer.u64(self.nonce);
+        writer.array(&self.keypair);
+        writer.array(&self.hash_password);
+        #[cfg(feature = "argon2")]
+        writer.array(&self.salt);
+    }
+
+    /// This method reads back an account written by `encode()`,
+    /// and returns `None` if the bytes don't represent a valid account.
+    #[cfg(feature = "keystore")]
+    pub(crate) fn decode(reader: &mut Reader) -> Option<Self> {
+        Some(Self {
+            first_name: reader.string()?,
+            last_name: reader.string()?,
+            balance: PositiveF64::new(reader.f64()?).ok()?,
+            debt: PositiveF64::new(reader.f64()?).ok()?,
+            overdraft_limit: PositiveF64::new(reader.f64()?).ok()?.value(),
+            nonce: reader.u64()?,
+            keypair: reader.array()?,
+            hash_password: reader.array()?,
+            #[cfg(feature = "argon2")]
+            salt: reader.array()?,
+        })
+    }
+}
+
+/// This function compares two byte slices in constant time, so that the position
+/// of the first mismatching byte can't be learnt from the time taken.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+impl Drop for Account {
+    fn drop(&mut self) {
+        for byte in self.keypair.iter_mut().chain(self.hash_password.iter_mut()) {
+            // volatile writes can't be optimized away, even if the memory is never read again
+            unsafe {
+                ptr::write_volatile(byte, 0);
+            }
+        }
+
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
 }
 
 impl fmt::Display for Account {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({} {}: {})", self.first_name, self.last_name, self.balance)
+        write!(f, "({} {}: {})", self.first_name, self.last_name, self.balance())
+    }
+}
+
+/// An enum to handle errors generated while moving money in and out of an `Account`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyError {
+    ZeroAmount,
+    Negative,
+    NotFinite,
+    InsufficientFunds,
+}
+
+impl From<InvalidNumber> for MoneyError {
+    fn from(e: InvalidNumber) -> Self {
+        match e {
+            InvalidNumber::NegativeValue => Self::Negative,
+            InvalidNumber::NotFinite | InvalidNumber::DivisionByZero => Self::NotFinite,
+        }
+    }
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::ZeroAmount => write!(f, "The amount can't be zero."),
+            Self::Negative => write!(f, "The amount can't be negative."),
+            Self::NotFinite => write!(f, "The amount must be finite."),
+            Self::InsufficientFunds => write!(f, "Insufficient funds."),
+        }
+    }
+}
+
+impl error::Error for MoneyError {}
+
+/// An enum to handle errors generated while moving the whole balance of an `Account` into another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepError {
+    WrongPassword,
+    EmptyBalance,
+    InvalidTransaction(ValidationError),
+    Money(MoneyError),
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::WrongPassword => write!(f, "Wrong password."),
+            Self::EmptyBalance => write!(f, "There is no money to move."),
+            Self::InvalidTransaction(e) => write!(f, "{}", e),
+            Self::Money(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for SweepError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn display_verbose_hides_secrets() {
+        let account = Account::new("Secret", "Account", "secret_password");
+
+        let to_hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        let displayed = account.display_verbose();
+
+        assert!(!displayed.contains(&to_hex(&account.keypair[..32])));
+        assert!(!displayed.contains(&to_hex(&account.hash_password)));
+        assert!(!displayed.contains(&format!("{:?}", &account.keypair[..32])));
+        assert!(!displayed.contains("secret_password"));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn same_password_hashes_differently_across_accounts() {
+        let first = Account::new("First", "Account", "same_password");
+        let second = Account::new("Second", "Account", "same_password");
+
+        assert_ne!(first.hash_password(), second.hash_password());
+        assert!(first.verify_password("same_password"));
+        assert!(second.verify_password("same_password"));
+    }
+
+    #[test]
+    fn secrets_are_zeroized_on_drop() {
+        let mut account = ManuallyDrop::new(Account::new("Secret", "Account", "secret_password"));
+        let clone = Account::clone(&account);
+
+        assert_ne!(account.keypair, [0; 64]);
+        assert_ne!(account.hash_password, [0; 64]);
+
+        // the memory of the account is still owned by the `ManuallyDrop`, so it can be read after the drop
+        unsafe {
+            ptr::drop_in_place(&mut *account as *mut Account);
+        }
+
+        assert_eq!(account.keypair, [0; 64]);
+        assert_eq!(account.hash_password, [0; 64]);
+        assert!(clone.verify_password("secret_password")); // the clone keeps its own copy
+    }
+
+    #[test]
+    fn overflowing_credits_are_rejected() {
+        let mut account = Account::new("Rich", "Account", "rich_password");
+        let mut other = Account::new("Other", "Account", "other_password");
+
+        account.add_money(f64::MAX).unwrap();
+        other.add_money(f64::MAX).unwrap();
+
+        assert_eq!(account.add_money(f64::MAX), Err(MoneyError::NotFinite));
+        assert_eq!(account.balance(), f64::MAX);
+        assert_eq!(other.transfer(&mut account, f64::MAX), Err(MoneyError::NotFinite));
+        assert_eq!(other.balance(), f64::MAX); // no partial transfer
+        assert_eq!(account.balance(), f64::MAX);
+    }
+
+    #[test]
+    fn sweeping_pays_the_fee_from_the_balance() {
+        let mut old_wallet = Account::new_with_balance("Old", "Wallet", "old_password", 10.0).unwrap();
+        let mut new_wallet = Account::new("New", "Wallet", "new_password");
+
+        let transaction = old_wallet.sweep_into(&mut new_wallet, "old_password").unwrap();
+
+        assert_eq!(transaction.amount(), 10.0 - SWEEP_FEE);
+        assert_eq!(transaction.fee(), SWEEP_FEE);
+        assert_eq!(transaction.amount() + transaction.fee(), 10.0);
+        assert_eq!(transaction.validate(transaction.hash()), Ok(()));
+        assert_eq!(old_wallet.balance(), 0.0);
+        assert_eq!(new_wallet.balance(), 10.0 - SWEEP_FEE);
+        assert_eq!(old_wallet.nonce(), 1);
+
+        let mut wallet = Account::new_with_balance("Other", "Wallet", "other_password", SWEEP_FEE).unwrap();
+
+        assert_eq!(wallet.sweep_into(&mut new_wallet, "other_password").unwrap_err(), SweepError::EmptyBalance);
+        assert_eq!(wallet.balance(), SWEEP_FEE);
+        assert_eq!(wallet.nonce(), 0);
+        assert_eq!(new_wallet.balance(), 10.0 - SWEEP_FEE);
     }
 }
@@ -1,7 +1,27 @@
-use crate::transaction::Transaction;
+use crate::{
+    transaction::Transaction,
+    coinbase::Coinbase,
+    encoding::{Writer, to_hex},
+    merkle,
+};
+use std::{fmt, error};
+use std::collections::HashSet;
 use std::convert::TryInto;
 use sha2::{Sha512, Digest};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+#[cfg(feature = "serde")]
+use {
+    serde::{Serialize, Deserialize},
+    chrono::SecondsFormat,
+    crate::encoding::from_hex,
+};
+
+/// The default number of leading zero bytes the hash of a block must have to satisfy the proof of work.
+pub const DIFFICULTY: usize = 2;
+
+/// The highest number of leading zero bytes a chain can require from the hash of its blocks:
+/// every additional byte makes mining a block about 256 times slower, so higher difficulties can't be reached.
+pub const MAX_DIFFICULTY: usize = 8;
 
 /// A structure to handle blocks for the blockchain of the currency.
 /// 
@@ -9,51 +29,147 @@ use chrono::{DateTime, Utc};
 /// - the index (the #0 block is the genesis block)
 /// - the SHA-512 hash of the previous block
 /// - the transactions of the block
-/// (the number of transactions per block is set while generating the blockchain)
+///   (the number of transactions per block is set while generating the blockchain)
+/// - the root of the Merkle tree built over the hashes of the transactions
+/// - the `Coinbase` rewarding the miner of the block, if any
+/// - the difficulty of the proof of work, as the number of leading zero bytes of the hash
 /// - the nonce, which is used for the proof of work
 /// - the `DateTime<Utc>` time when the block was generated
+///   (the genesis block has a fixed time, the Unix epoch, so that it can be reproduced)
 /// - the hash of the block generated
 #[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     index: usize,
     prev_hash: [u8; 64],
     transactions: Vec<Transaction>,
+    merkle_root: [u8; 64],
+    coinbase: Option<Coinbase>,
+    difficulty: usize,
     nonce: u128,
     time: DateTime<Utc>,
     hash: [u8; 64],
 }
 
 impl Block {
-    /// Generates a new `Block`.
+    /// Generates a new `Block`, whose hash has `difficulty` leading zero bytes.
     /// 
     /// # Example
     /// ```
-    /// # use blockchain::{block::Block, transaction::Transaction, account::Account};
+    /// # use blockchain::{block::{Block, DIFFICULTY}, transaction::Transaction, account::Account};
     /// let mut glenn = Account::new("Glenn", "Paris", "glenn_paris_PassWord88");
     /// let william = Account::new("William", "Brown", "WilliamTheConqueror22");
-    /// glenn.add_money(20.0);
+    /// glenn.add_money(20.0).unwrap();
     /// 
-    /// let transaction = Transaction::new(glenn, william, 20.0, "glenn_paris_PassWord88");
+    /// let transaction = Transaction::new(glenn, william, 20.0, "glenn_paris_PassWord88").unwrap();
     ///
     /// let genesis = Block::default(); // that's the actual genesis block
     ///
-    /// let new_block = Block::new(1, genesis.hash(), vec![transaction]);
+    /// let new_block = Block::new(1, genesis.hash(), vec![transaction], DIFFICULTY);
     /// 
     /// assert_eq!(new_block.index(), 1);
     /// ```
-    pub fn new(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>) -> Self {
-        let mut block = Self {
+    pub fn new(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, difficulty: usize) -> Self {
+        Block::build(index, prev_hash, transactions, None, Utc::now(), difficulty)
+    }
+
+    /// Generates a new `Block`, whose miner is rewarded by the `Coinbase`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::{block::{Block, DIFFICULTY}, coinbase::Coinbase, positive_f64::PositiveF64};
+    /// let genesis = Block::default();
+    /// 
+    /// let new_block = Block::with_coinbase(1, genesis.hash(), Vec::new(), Coinbase::new("miner_address", PositiveF64::new(50.0).unwrap()), DIFFICULTY);
+    /// 
+    /// assert_eq!(new_block.coinbase().unwrap().reward(), 50.0);
+    /// ```
+    pub fn with_coinbase(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, coinbase: Coinbase, difficulty: usize) -> Self {
+        Block::build(index, prev_hash, transactions, Some(coinbase), Utc::now(), difficulty)
+    }
+
+    /// Generates a new `Block` like `Block::new()`, but gives up the proof of work
+    /// after `max_attempts` hashes, if given, instead of mining until a valid nonce is found.
+    /// 
+    /// The function returns a `Result<Block, MiningError>`, because if no nonce satisfies
+    /// the difficulty within the attempts, or every nonce has been tried, a `MiningError::Exhausted` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, MiningError};
+    /// let genesis = Block::default();
+    /// 
+    /// assert!(Block::try_new(1, genesis.hash(), Vec::new(), 0, Some(1)).is_ok());
+    /// assert_eq!(Block::try_new(1, genesis.hash(), Vec::new(), 64, Some(100)), Err(MiningError::Exhausted));
+    /// ```
+    pub fn try_new(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, difficulty: usize, max_attempts: Option<u128>) -> Result<Self, MiningError> {
+        let mut block = Block::unmined(index, prev_hash, transactions, None, Utc::now(), difficulty);
+
+        block.calculate_hash(max_attempts)?;
+
+        Ok(block)
+    }
+
+    /// Generates a new `Block` like `Block::new()`, but splits the proof of work between `workers` threads:
+    /// the n-th worker tries the nonces n, n + `workers`, n + 2 * `workers` and so on,
+    /// and every worker stops as soon as any of them finds a valid nonce.
+    /// 
+    /// The hash of the block always satisfies the difficulty, but the nonce found may change between runs.
+    /// If `workers` is zero, a single worker is used.
+    /// 
+    /// The function returns a `Result<Block, MiningError>`, because if every nonce has been tried
+    /// without satisfying the difficulty, a `MiningError::Exhausted` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis = Block::default();
+    /// let block = Block::try_new_parallel(1, genesis.hash(), Vec::new(), DIFFICULTY, 4).unwrap();
+    /// 
+    /// assert!(block.verify_nonce(DIFFICULTY));
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn try_new_parallel(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, difficulty: usize, workers: usize) -> Result<Self, MiningError> {
+        let mut block = Block::unmined(index, prev_hash, transactions, None, Utc::now(), difficulty);
+
+        block.calculate_hash_parallel(workers)?;
+
+        Ok(block)
+    }
+
+    /// Generates the genesis block, whose time is fixed to the Unix epoch, so that two chains
+    /// with the same difficulty and the same `Coinbase` have the same genesis block.
+    pub(crate) fn genesis(coinbase: Option<Coinbase>, difficulty: usize) -> Self {
+        let epoch = Utc.timestamp_opt(0, 0).unwrap();
+
+        Block::build(0, [0; 64], Vec::new(), coinbase, epoch, difficulty)
+    }
+
+    /// Generates a new `Block` with the given time, performing the proof of work with the given difficulty.
+    pub(crate) fn build(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, coinbase: Option<Coinbase>, time: DateTime<Utc>, difficulty: usize) -> Self {
+        let mut block = Block::unmined(index, prev_hash, transactions, coinbase, time, difficulty);
+
+        block
+            .calculate_hash(None)
+            .expect("Error mining the block, every nonce has been tried.");
+
+        block
+    }
+
+    /// Generates a new `Block` with the given time, without performing the proof of work.
+    fn unmined(index: usize, prev_hash: [u8; 64], transactions: Vec<Transaction>, coinbase: Option<Coinbase>, time: DateTime<Utc>, difficulty: usize) -> Self {
+        let transactions_hashes: Vec<[u8; 64]> = transactions.iter().map(|t| t.hash()).collect();
+
+        Self {
             index,
             prev_hash,
             transactions,
+            merkle_root: merkle::merkle_root(&transactions_hashes),
+            coinbase,
+            difficulty,
             nonce: 0,
-            time: Utc::now(),
+            time,
             hash: [0; 64],
-        };
-
-        block.calculate_hash();
-
-        block
+        }
     }
 
     /// This method returns the hash of the block, since the `hash` field isn't `pub`.
@@ -63,12 +179,95 @@ impl Block {
     /// # use blockchain::block::Block;
     /// let genesis_block = Block::default();
     /// 
-    /// assert_eq!(genesis_block.hash().len(), 64); // the hash changes everytime because of the time
+    /// assert_eq!(genesis_block.hash(), Block::default().hash()); // the genesis block has a fixed time
     /// ```
     pub fn hash(&self) -> [u8; 64] {
         self.hash
     }
 
+    /// This method checks that the nonce of the block actually produces the hash of the block,
+    /// and that the hash satisfies the proof of work for the given difficulty.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// 
+    /// assert!(genesis_block.verify_nonce(DIFFICULTY));
+    /// ```
+    pub fn verify_nonce(&self, difficulty: usize) -> bool {
+        self.compute_hash() == self.hash && Block::satisfies_difficulty(&self.hash, difficulty)
+    }
+
+    /// This method checks that the hash of the block satisfies the proof of work,
+    /// for the difficulty the block was mined with.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::Block;
+    /// let genesis_block = Block::default();
+    /// 
+    /// assert!(genesis_block.verify_pow());
+    /// ```
+    pub fn verify_pow(&self) -> bool {
+        Block::satisfies_difficulty(&self.hash, self.difficulty)
+    }
+
+    /// This method checks that the block is well-formed, and that it follows the block with the given hash.
+    /// 
+    /// The function returns a `Result<(), BlockError>`, because
+    /// if the hash of the block doesn't match with the hash recomputed from its fields, a `BlockError::InvalidHash` error is returned,
+    /// if the Merkle root doesn't match with the hashes of the transactions, a `BlockError::InvalidMerkleRoot` error is returned,
+    /// if a transaction appears more than once, a `BlockError::DuplicateTransaction` error is returned,
+    /// if the previous hash of the block isn't `prev_hash`, a `BlockError::InvalidPrevHash` error is returned,
+    /// and if the hash doesn't satisfy the proof of work, a `BlockError::InvalidProofOfWork` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, BlockError, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(new_block.validate(&genesis_block.hash()), Ok(()));
+    /// assert_eq!(new_block.validate(&[0; 64]), Err(BlockError::InvalidPrevHash));
+    /// ```
+    pub fn validate(&self, prev_hash: &[u8; 64]) -> Result<(), BlockError> {
+        if self.compute_hash() != self.hash {
+            Err(BlockError::InvalidHash)
+        } else if !self.verify_merkle_root() {
+            Err(BlockError::InvalidMerkleRoot)
+        } else if self.has_duplicate_transactions() {
+            Err(BlockError::DuplicateTransaction)
+        } else if self.prev_hash != *prev_hash {
+            Err(BlockError::InvalidPrevHash)
+        } else if !self.verify_pow() {
+            Err(BlockError::InvalidProofOfWork)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// This method returns the difficulty the block was mined with, since the `difficulty` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), 1);
+    /// 
+    /// assert_eq!(genesis_block.difficulty(), DIFFICULTY);
+    /// assert_eq!(new_block.difficulty(), 1);
+    /// ```
+    pub fn difficulty(&self) -> usize {
+        self.difficulty
+    }
+
+    /// This function checks if the first `difficulty` bytes of the hash are zero, as the proof of work requires;
+    /// since the hash has 64 bytes, a difficulty greater than 64 can't be satisfied.
+    fn satisfies_difficulty(hash: &[u8; 64], difficulty: usize) -> bool {
+        difficulty <= hash.len() && hash[..difficulty].iter().all(|b| *b == 0)
+    }
+
     /// This method returns the index of the block, since the `index` field isn't `pub`.
     /// 
     /// # Example
@@ -78,50 +277,743 @@ impl Block {
     /// 
     /// assert_eq!(genesis_block.index(), 0);
     /// ```
-    #[allow(dead_code)]
     pub fn index(&self) -> usize {
         self.index
     }
 
+    /// This method returns the hash of the previous block, since the `prev_hash` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(new_block.prev_hash(), genesis_block.hash());
+    /// ```
+    pub fn prev_hash(&self) -> [u8; 64] {
+        self.prev_hash
+    }
+
+    /// This method returns the `DateTime<Utc>` time when the block was generated, since the `time` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert!(new_block.time() >= genesis_block.time());
+    /// ```
+    pub fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
+
+    /// This method returns the time when the block was generated, as seconds since the Unix epoch;
+    /// the time is part of the hash, so it can't be changed without mining the block again.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(genesis_block.timestamp(), 0);
+    /// assert_eq!(new_block.timestamp(), new_block.time().timestamp() as u64);
+    /// ```
+    pub fn timestamp(&self) -> u64 {
+        self.time.timestamp() as u64
+    }
+
+    /// This method returns the `Coinbase` of the block, since the `coinbase` field isn't `pub`;
+    /// the blocks mined without a miner have no coinbase, as the genesis block, unless
+    /// its coinbase premines some money with `BlockChain::premine()`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::Block;
+    /// let genesis_block = Block::default();
+    /// 
+    /// assert_eq!(genesis_block.coinbase(), None);
+    /// ```
+    pub fn coinbase(&self) -> Option<&Coinbase> {
+        self.coinbase.as_ref()
+    }
+
+    /// This method returns the root of the Merkle tree built over the hashes of the transactions of the block,
+    /// since the `merkle_root` field isn't `pub`; the root of a block without transactions is made of zeros.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::{block::{Block, DIFFICULTY}, transaction::Transaction, account::Account};
+    /// let mut tegan = Account::new("Tegan", "Jovanka", "tegan_jovanka_1981");
+    /// let turlough = Account::new("Vislor", "Turlough", "turlough_trion_1983");
+    /// tegan.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(tegan, turlough, 5.0, "tegan_jovanka_1981").unwrap();
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), vec![transaction], DIFFICULTY);
+    /// 
+    /// assert_eq!(genesis_block.merkle_root(), [0; 64]);
+    /// assert_ne!(new_block.merkle_root(), [0; 64]);
+    /// ```
+    pub fn merkle_root(&self) -> [u8; 64] {
+        self.merkle_root
+    }
+
+    /// This method returns the proof that the transaction with the given hash is in the block,
+    /// made of the hashes of the siblings on the path from the transaction to the Merkle root,
+    /// each with `true` if the sibling is the left node of its pair, so that the transaction can be checked with `verify_merkle_proof()` without the other transactions;
+    /// if the transaction isn't in the block, `None` is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::{block::{self, Block, DIFFICULTY}, transaction::Transaction, account::Account};
+    /// let mut ace = Account::new("Dorothy", "McShane", "Nitro-9_Ace");
+    /// let mel = Account::new("Melanie", "Bush", "mel_bush_1986");
+    /// ace.add_money(20.0).unwrap();
+    /// 
+    /// let transactions: Vec<Transaction> = (1..=3)
+    ///     .map(|amount| Transaction::new(ace.clone(), mel.clone(), amount as f64, "Nitro-9_Ace").unwrap())
+    ///     .collect();
+    /// 
+    /// let block = Block::new(1, Block::default().hash(), transactions.clone(), DIFFICULTY);
+    /// let proof = block.merkle_proof(&transactions[2].hash()).unwrap();
+    /// 
+    /// assert!(block::verify_merkle_proof(&transactions[2].hash(), &proof, &block.merkle_root()));
+    /// assert!(block.merkle_proof(&[0; 64]).is_none());
+    /// ```
+    pub fn merkle_proof(&self, tx_hash: &[u8; 64]) -> Option<Vec<([u8; 64], bool)>> {
+        let transactions_hashes: Vec<[u8; 64]> = self.transactions.iter().map(|t| t.hash()).collect();
+
+        let index = transactions_hashes.iter().position(|hash| hash == tx_hash)?;
+
+        Some(merkle::merkle_path(&transactions_hashes, index))
+    }
+
+    /// This method checks if a transaction appears more than once in the block,
+    /// which the Merkle root alone can't detect, since the last transaction of an odd level is paired with itself.
+    fn has_duplicate_transactions(&self) -> bool {
+        let mut seen = HashSet::new();
+
+        !self.transactions.iter().all(|t| seen.insert(t.hash()))
+    }
+
+    /// This method checks that the Merkle root of the block matches with the hashes of its transactions.
+    pub(crate) fn verify_merkle_root(&self) -> bool {
+        let transactions_hashes: Vec<[u8; 64]> = self.transactions.iter().map(|t| t.hash()).collect();
+
+        merkle::merkle_root(&transactions_hashes) == self.merkle_root
+    }
+
+    /// This method returns the distance between the hash of the block and the hash of another block,
+    /// interpreting the first 16 bytes of each hash as a big-endian number.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(genesis_block.hash_distance(&genesis_block), 0);
+    /// assert_eq!(genesis_block.hash_distance(&new_block), new_block.hash_distance(&genesis_block));
+    /// ```
+    pub fn hash_distance(&self, other: &Block) -> u128 {
+        let leading = |hash: [u8; 64]| u128::from_be_bytes(hash[..16].try_into().unwrap());
+
+        leading(self.hash).abs_diff(leading(other.hash))
+    }
+
+    /// This method returns the transactions of the block, since the `transactions` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::{block::{Block, DIFFICULTY}, transaction::Transaction, account::Account};
+    /// let mut nyssa = Account::new("Nyssa", "Traken", "nyssa_of_traken");
+    /// let adric = Account::new("Adric", "Alzarius", "adric_alzarius_1980");
+    /// nyssa.add_money(15.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(nyssa, adric, 5.0, "nyssa_of_traken").unwrap();
+    /// let genesis_block = Block::default();
+    /// let new_block = Block::new(1, genesis_block.hash(), vec![transaction.clone()], DIFFICULTY);
+    /// 
+    /// assert!(genesis_block.transactions().is_empty());
+    /// assert_eq!(new_block.transactions(), [transaction]);
+    /// ```
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// This method consumes the block, and returns its transactions without cloning them.
+    pub(crate) fn into_transactions(self) -> Vec<Transaction> {
+        self.transactions
+    }
+
+    /// This method returns the nonce found by the proof of work, since the `nonce` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::block::Block;
+    /// let genesis_block = Block::default();
+    /// let easy_block = Block::new(1, genesis_block.hash(), Vec::new(), 0);
+    /// 
+    /// assert_eq!(easy_block.nonce(), 0);
+    /// ```
+    pub fn nonce(&self) -> u128 {
+        self.nonce
+    }
+
+    /// This method writes the fields of the block as bytes, in a canonical layout;
+    /// the transactions are written as their hashes, which commit to all their fields,
+    /// so that no secret of the accounts involved is written.
+    pub(crate) fn encode(&self, writer: &mut Writer) {
+        writer.u64(self.index as u64);
+        writer.array(&self.prev_hash);
+        writer.u64(self.transactions.len() as u64);
+
+        for transaction in &self.transactions {
+            writer.array(&transaction.hash());
+        }
+
+        Block::encode_coinbase(self.coinbase.as_ref(), writer);
+
+        writer.u64(self.difficulty as u64);
+        writer.array(&self.nonce.to_le_bytes());
+        writer.time(self.time);
+        writer.array(&self.hash);
+    }
+
+    /// This function writes the `Coinbase` of a block, if any, as bytes.
+    fn encode_coinbase(coinbase: Option<&Coinbase>, writer: &mut Writer) {
+        match coinbase {
+            None => writer.u64(0),
+            Some(coinbase) => {
+                writer.u64(1);
+                writer.u64(coinbase.payouts().len() as u64);
+
+                for (miner, reward) in coinbase.payouts() {
+                    writer.string(miner);
+                    writer.f64(reward.value());
+                }
+            },
+        }
+    }
+
     /// This method is called when a new block is generated,
     /// and it is used to calculate the SHA-512 hash of the new block.
     /// 
     /// The hash is calculated by using:
     /// - the index of the block
     /// - the previous hash
-    /// - the `Transaction`s hashes
+    /// - the Merkle root of the `Transaction`s hashes
+    /// - the `Coinbase`
     /// - the `DateTime<Utc>` time when the block was generated
+    /// - the difficulty of the proof of work
     /// - the nonce used for the proof of work
     /// 
-    /// The proof of work is checked in the condition of the while loop.
-    fn calculate_hash(&mut self) {
-        while self.hash[0..2] != [69, 69] {
-            let mut hasher = Sha512::new();
-
-            let transactions_hashes = self.transactions.iter().fold(String::new(), |acc, t| format!("{:?}{:?}", acc, t.hash()));
-            
-            let digest = format!("{}{:?}{}{:?}{}",
-                self.index,
-                self.prev_hash,
-                transactions_hashes,
-                self.time,
-                self.nonce
-            );
-    
-            hasher.update(digest.as_bytes());
-            
-            self.hash = hasher
-                .finalize()[..]
-                .try_into()
-                .expect("Error generating the SHA-512 hash of the block.");
-
-            self.nonce += 1;
+    /// The fields are hashed as raw bytes, in the same layout used by `.encode()`.
+    /// 
+    /// The proof of work, which requires the first `difficulty` bytes of the hash to be zero,
+    /// is checked in the condition of the loop, and the nonce is incremented only if the hash doesn't satisfy it.
+    /// 
+    /// Since the nonce is the only field which changes between the attempts, the other fields
+    /// are hashed only once, and every attempt only hashes the nonce on top of them.
+    /// 
+    /// If `max_attempts` hashes have been computed, or the nonce would wrap around after `u128::MAX`,
+    /// the proof of work is given up and a `MiningError::Exhausted` error is returned.
+    fn calculate_hash(&mut self, max_attempts: Option<u128>) -> Result<(), MiningError> {
+        let prefix = self.prefix_hasher();
+        let mut attempts: u128 = 0;
+
+        loop {
+            if max_attempts.is_some_and(|max_attempts| attempts >= max_attempts) {
+                return Err(MiningError::Exhausted);
+            }
+
+            self.hash = Block::hash_nonce(&prefix, self.nonce);
+            attempts = attempts.saturating_add(1);
+
+            if self.verify_pow() {
+                return Ok(());
+            }
+
+            self.nonce = self.nonce.checked_add(1).ok_or(MiningError::Exhausted)?;
         }
     }
+
+    /// This method performs the proof of work like `.calculate_hash()`, splitting the nonces between `workers` threads.
+    #[cfg(feature = "parallel")]
+    fn calculate_hash_parallel(&mut self, workers: usize) -> Result<(), MiningError> {
+        use std::{thread, sync::atomic::{AtomicBool, Ordering}};
+
+        let workers = workers.max(1) as u128;
+        let found = AtomicBool::new(false);
+        let prefix = self.prefix_hasher();
+        let difficulty = self.difficulty;
+
+        let (nonce, hash) = thread::scope(|scope| {
+            let found = &found;
+            let prefix = &prefix;
+
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| scope.spawn(move || {
+                    let mut nonce = worker;
+
+                    while !found.load(Ordering::Relaxed) {
+                        let hash = Block::hash_nonce(prefix, nonce);
+
+                        if Block::satisfies_difficulty(&hash, difficulty) {
+                            found.store(true, Ordering::Relaxed);
+
+                            return Some((nonce, hash));
+                        }
+
+                        nonce = nonce.checked_add(workers)?;
+                    }
+
+                    None
+                }))
+                .collect();
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().expect("Error joining a mining worker."))
+                .next()
+                .ok_or(MiningError::Exhausted)
+        })?;
+
+        self.nonce = nonce;
+        self.hash = hash;
+
+        Ok(())
+    }
+
+    /// This method computes the SHA-512 hash of the block from its fields,
+    /// using the current nonce, without performing the proof of work.
+    pub(crate) fn compute_hash(&self) -> [u8; 64] {
+        Block::hash_nonce(&self.prefix_hasher(), self.nonce)
+    }
+
+    /// This method returns a SHA-512 hasher which has already been fed every field of the block but the nonce.
+    fn prefix_hasher(&self) -> Sha512 {
+        Block::hash_prefix(self.index, self.prev_hash, &self.merkle_root, self.coinbase.as_ref(), self.time, self.difficulty)
+    }
+
+    /// This function computes the SHA-512 hash of a block given its fields,
+    /// so that the hash can be checked even without the `Transaction`s of the block.
+    pub(crate) fn hash_fields(index: usize, prev_hash: [u8; 64], merkle_root: &[u8; 64], coinbase: Option<&Coinbase>, time: DateTime<Utc>, difficulty: usize, nonce: u128) -> [u8; 64] {
+        Block::hash_nonce(&Block::hash_prefix(index, prev_hash, merkle_root, coinbase, time, difficulty), nonce)
+    }
+
+    /// This function returns a SHA-512 hasher which has already been fed the bytes of the given fields of a block,
+    /// which are followed by the nonce in the hash of the block; the transactions are committed to by the Merkle root.
+    fn hash_prefix(index: usize, prev_hash: [u8; 64], merkle_root: &[u8; 64], coinbase: Option<&Coinbase>, time: DateTime<Utc>, difficulty: usize) -> Sha512 {
+        let mut writer = Writer::default();
+
+        writer.u64(index as u64);
+        writer.array(&prev_hash);
+        writer.array(merkle_root);
+        Block::encode_coinbase(coinbase, &mut writer);
+        writer.time(time);
+        writer.u64(difficulty as u64);
+
+        let mut hasher = Sha512::new();
+
+        hasher.update(writer.bytes());
+
+        hasher
+    }
+
+    /// This function computes the SHA-512 hash of a block with the nonce,
+    /// from a clone of the hasher returned by `Block::hash_prefix()`.
+    fn hash_nonce(prefix: &Sha512, nonce: u128) -> [u8; 64] {
+        let mut hasher = prefix.clone();
+
+        hasher.update(nonce.to_le_bytes());
+        
+        hasher
+            .finalize()[..]
+            .try_into()
+            .expect("Error generating the SHA-512 hash of the block.")
+    }
+}
+
+/// This function checks a proof returned by `Block::merkle_proof()`, that the transaction with the hash `leaf`
+/// is among the transactions of the block with the Merkle root `root`.
+/// 
+/// # Example
+/// ```
+/// # use blockchain::{block::{self, Block, DIFFICULTY}, transaction::Transaction, account::Account};
+/// let mut ian = Account::new("Ian", "Chesterton", "coal_hill_school");
+/// let barbara = Account::new("Barbara", "Wright", "history_teacher_1963");
+/// ian.add_money(20.0).unwrap();
+/// 
+/// let first = Transaction::new(ian.clone(), barbara.clone(), 4.0, "coal_hill_school").unwrap();
+/// let second = Transaction::new(ian, barbara, 6.0, "coal_hill_school").unwrap();
+/// 
+/// let block = Block::new(1, Block::default().hash(), vec![first.clone(), second.clone()], DIFFICULTY);
+/// let proof = block.merkle_proof(&first.hash()).unwrap();
+/// 
+/// assert!(block::verify_merkle_proof(&first.hash(), &proof, &block.merkle_root()));
+/// assert!(!block::verify_merkle_proof(&second.hash(), &proof, &block.merkle_root()));
+/// ```
+pub fn verify_merkle_proof(leaf: &[u8; 64], proof: &[([u8; 64], bool)], root: &[u8; 64]) -> bool {
+    merkle::root_from_path(leaf, proof) == *root
 }
 
 impl Default for Block {
     fn default() -> Self {
-        Block::new(0, [0; 64], Vec::new())
+        Block::genesis(None, DIFFICULTY)
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Block #{} [{}] prev [{}] nonce {} ({} transactions)",
+            self.index,
+            to_hex(&self.hash[..8]),
+            to_hex(&self.prev_hash[..8]),
+            self.nonce,
+            self.transactions.len(),
+        )?;
+
+        for transaction in &self.transactions {
+            write!(f, "\n    {}", transaction)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The representation of a `Block` used by `serde`:
+/// the hashes are written as hex strings, and the time as an RFC 3339 string, so that no precision is lost.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerdeBlock {
+    index: usize,
+    prev_hash: String,
+    transactions: Vec<Transaction>,
+    merkle_root: String,
+    coinbase: Option<Coinbase>,
+    difficulty: usize,
+    nonce: u128,
+    timestamp: String,
+    hash: String,
+}
+
+/// A `Block` is serialized with all its fields; the hash is serialized as it is,
+/// so it must be checked with `.verify_nonce()` after deserializing the block.
+#[cfg(feature = "serde")]
+impl Serialize for Block {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeBlock {
+            index: self.index,
+            prev_hash: to_hex(&self.prev_hash),
+            transactions: self.transactions.clone(),
+            merkle_root: to_hex(&self.merkle_root),
+            coinbase: self.coinbase.clone(),
+            difficulty: self.difficulty,
+            nonce: self.nonce,
+            timestamp: self.time.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            hash: to_hex(&self.hash),
+        }.serialize(serializer)
+    }
+}
+
+/// A `Block` is deserialized without performing the proof of work again, nor checking its hash.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let block = SerdeBlock::deserialize(deserializer)?;
+
+        let array = |hex: &str| {
+            from_hex(hex)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| D::Error::custom("invalid hex array"))
+        };
+
+        Ok(Self {
+            index: block.index,
+            prev_hash: array(&block.prev_hash)?,
+            transactions: block.transactions,
+            merkle_root: array(&block.merkle_root)?,
+            coinbase: block.coinbase,
+            difficulty: block.difficulty,
+            nonce: block.nonce,
+            time: DateTime::parse_from_rfc3339(&block.timestamp)
+                .map_err(D::Error::custom)?
+                .with_timezone(&Utc),
+            hash: array(&block.hash)?,
+        })
+    }
+}
+
+/// An enum to handle errors generated while validating a `Block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockError {
+    InvalidHash,
+    InvalidMerkleRoot,
+    DuplicateTransaction,
+    InvalidPrevHash,
+    InvalidProofOfWork,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidHash => write!(f, "The hash of the block doesn't match with its fields."),
+            Self::InvalidMerkleRoot => write!(f, "The Merkle root of the block doesn't match with its transactions."),
+            Self::DuplicateTransaction => write!(f, "The block contains the same transaction more than once."),
+            Self::InvalidPrevHash => write!(f, "The block doesn't follow the previous block."),
+            Self::InvalidProofOfWork => write!(f, "The block doesn't satisfy the proof of work."),
+        }
+    }
+}
+
+impl error::Error for BlockError {}
+
+/// An enum to handle errors generated while mining a `Block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningError {
+    Exhausted,
+}
+
+impl fmt::Display for MiningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Exhausted => write!(f, "No nonce satisfying the difficulty was found within the attempts."),
+        }
+    }
+}
+
+impl error::Error for MiningError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account::Account, positive_f64::PositiveF64};
+
+    #[test]
+    fn tampered_nonce_fails_verification() {
+        let mut block = Block::new(1, [0; 64], Vec::new(), DIFFICULTY);
+
+        assert!(block.verify_nonce(DIFFICULTY));
+
+        block.nonce = block.nonce.wrapping_sub(1);
+
+        assert!(!block.verify_nonce(DIFFICULTY));
+    }
+
+    #[test]
+    fn valid_block_and_mutated_nonce() {
+        let genesis = Block::default();
+        let mut block = Block::build(1, genesis.hash(), Vec::new(), None, genesis.time(), DIFFICULTY);
+
+        assert!(block.verify_pow());
+        assert_eq!(block.validate(&genesis.hash()), Ok(()));
+
+        block.nonce += 1;
+
+        assert!(block.verify_pow()); // the stored hash isn't changed
+        assert_eq!(block.validate(&genesis.hash()), Err(BlockError::InvalidHash));
+
+        block.hash = block.compute_hash();
+
+        assert_eq!(block.validate(&genesis.hash()), Err(BlockError::InvalidProofOfWork));
+    }
+
+    #[test]
+    fn hash_distance_between_blocks() {
+        let genesis = Block::default();
+        let block = Block::new(1, genesis.hash(), Vec::new(), DIFFICULTY);
+
+        assert_eq!(block.hash_distance(&block), 0);
+        assert_ne!(block.hash_distance(&genesis), 0);
+    }
+
+    #[test]
+    fn any_changed_transaction_changes_the_merkle_root() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let transactions: Vec<Transaction> = (1..=3)
+            .map(|amount| Transaction::new(sender.clone(), receiver.clone(), amount as f64, "sender_password").unwrap())
+            .collect();
+
+        let block = Block::new(1, [0; 64], transactions.clone(), 1);
+
+        assert!(block.verify_merkle_root());
+
+        let mut reordered = block.clone();
+        reordered.transactions.swap(0, 1);
+
+        assert!(reordered.verify_nonce(1)); // the header isn't changed
+        assert!(!reordered.verify_merkle_root());
+
+        for i in 0..transactions.len() {
+            let mut changed = transactions.clone();
+            changed[i] = Transaction::new(sender.clone(), receiver.clone(), 10.0, "sender_password").unwrap();
+
+            let changed_block = Block::new(1, [0; 64], changed, 1);
+
+            assert_ne!(changed_block.merkle_root(), block.merkle_root());
+        }
+    }
+
+    #[test]
+    fn duplicated_transactions_make_the_block_invalid() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let transactions: Vec<Transaction> = (1..=3)
+            .map(|amount| Transaction::new(sender.clone(), receiver.clone(), amount as f64, "sender_password").unwrap())
+            .collect();
+
+        let mut duplicated = transactions.clone();
+        duplicated.push(transactions[2].clone());
+
+        let block = Block::new(1, [0; 64], transactions, 1);
+        let duplicated_block = Block::new(1, [0; 64], duplicated, 1);
+
+        assert_eq!(block.validate(&[0; 64]), Ok(()));
+        assert_eq!(duplicated_block.merkle_root(), block.merkle_root()); // the last leaf is paired with itself
+        assert_eq!(duplicated_block.validate(&[0; 64]), Err(BlockError::DuplicateTransaction));
+    }
+
+    #[test]
+    fn mined_hash_starts_with_zero_bytes() {
+        let block = Block::new(1, [0; 64], Vec::new(), 1);
+
+        assert_eq!(block.hash()[0], 0);
+        assert!(block.verify_nonce(1));
+
+        let genesis = Block::default();
+
+        assert_eq!(genesis.hash()[..DIFFICULTY], [0; DIFFICULTY]);
+    }
+
+    #[test]
+    fn impossible_difficulty_exhausts_the_attempts() {
+        let result = Block::try_new(1, [0; 64], Vec::new(), 64, Some(1_000));
+
+        assert_eq!(result, Err(MiningError::Exhausted));
+
+        let mut block = Block::unmined(1, [0; 64], Vec::new(), None, Utc::now(), 64);
+        block.nonce = u128::MAX;
+
+        assert_eq!(block.calculate_hash(None), Err(MiningError::Exhausted));
+        assert_eq!(block.nonce(), u128::MAX);
+        assert_eq!(Block::try_new(1, [0; 64], Vec::new(), 1, Some(0)), Err(MiningError::Exhausted));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_miner_satisfies_the_difficulty() {
+        let genesis = Block::default();
+
+        for workers in [0, 1, 4] {
+            let block = Block::try_new_parallel(1, genesis.hash(), Vec::new(), DIFFICULTY, workers).unwrap();
+
+            assert_eq!(block.hash()[..DIFFICULTY], [0; DIFFICULTY]);
+            assert_eq!(block.validate(&genesis.hash()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn prefix_hasher_matches_the_whole_digest() {
+        let block = Block::new(1, [0; 64], Vec::new(), 1);
+
+        for nonce in [0, 1, block.nonce(), u128::MAX] {
+            let mut writer = Writer::default();
+
+            writer.u64(block.index as u64);
+            writer.array(&block.prev_hash);
+            writer.array(&block.merkle_root);
+            writer.u64(0);
+            writer.time(block.time);
+            writer.u64(block.difficulty as u64);
+            writer.array(&nonce.to_le_bytes());
+
+            let naive: [u8; 64] = Sha512::digest(&writer.bytes())[..].try_into().unwrap();
+
+            assert_eq!(Block::hash_nonce(&block.prefix_hasher(), nonce), naive);
+        }
+
+        assert!(block.verify_nonce(1));
+    }
+
+    #[test]
+    fn hash_of_a_fixed_block_is_pinned() {
+        let coinbase = Coinbase::new("miner_address", PositiveF64::new(50.0).unwrap());
+        let time = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+
+        let mut block = Block::unmined(3, [7; 64], Vec::new(), Some(coinbase), time, 0);
+        block.nonce = 42;
+
+        assert_eq!(
+            to_hex(&block.compute_hash()),
+            "9e4c5cef612d3fdc43e048697bbc0a6b07decbc29248c42e18941693654c92d5009399089e5c3704792134e215b140167e515356ad283094d456b56e5b9f6fc2",
+        );
+    }
+
+    #[test]
+    fn merkle_proofs_verify_only_the_members_of_the_block() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let transactions: Vec<Transaction> = (1..=5)
+            .map(|amount| Transaction::new(sender.clone(), receiver.clone(), amount as f64, "sender_password").unwrap())
+            .collect();
+
+        let block = Block::new(1, [0; 64], transactions.clone(), 1);
+        let root = block.merkle_root();
+
+        for transaction in &transactions {
+            let mut proof = block.merkle_proof(&transaction.hash()).unwrap();
+
+            assert_eq!(proof.len(), 3);
+            assert!(verify_merkle_proof(&transaction.hash(), &proof, &root));
+
+            let top = proof.len() - 1;
+            proof[top].1 = !proof[top].1; // the sibling of the top level on the wrong side
+
+            assert!(!verify_merkle_proof(&transaction.hash(), &proof, &root));
+        }
+
+        let outsider = Transaction::new(sender.clone(), receiver.clone(), 50.0, "sender_password").unwrap();
+        let proof = block.merkle_proof(&transactions[0].hash()).unwrap();
+
+        assert!(block.merkle_proof(&outsider.hash()).is_none());
+        assert!(!verify_merkle_proof(&outsider.hash(), &proof, &root));
+
+        let single = Block::new(1, [0; 64], vec![outsider.clone()], 1);
+
+        assert_eq!(single.merkle_proof(&outsider.hash()), Some(Vec::new()));
+        assert!(verify_merkle_proof(&outsider.hash(), &[], &single.merkle_root()));
+    }
+
+    #[test]
+    fn displayed_block_shows_index_and_transactions() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let transactions = vec![
+            Transaction::new(sender.clone(), receiver.clone(), 2.0, "sender_password").unwrap(),
+            Transaction::new(sender, receiver, 3.0, "sender_password").unwrap(),
+        ];
+        let block = Block::new(7, [0; 64], transactions, 1);
+        let displayed = block.to_string();
+
+        assert!(displayed.starts_with("Block #7 "));
+        assert!(displayed.contains("(2 transactions)"));
+        assert!(displayed.contains(&block.transactions()[1].short_id()));
+        assert_eq!(displayed.lines().count(), 3);
+        assert!(!displayed.contains(&to_hex(&block.hash())));
     }
 }
@@ -1,8 +1,37 @@
+use std::{fmt, error, mem};
+use std::collections::{HashSet, HashMap};
+use std::sync::Arc;
+use std::convert::TryInto;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use sha2::{Sha512, Digest};
 use crate::{
     account::Account,
     transaction::{Transaction, ValidationError},
-    block::Block,
+    block::{Block, BlockError, MAX_DIFFICULTY},
+    coinbase::Coinbase,
+    positive_f64::{PositiveF64, InvalidNumber},
+    proof::BalanceProof,
+    encoding::{Writer, Reader},
+    merkle,
 };
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+#[cfg(feature = "serde")]
+use {
+    std::{fs, io, path::Path},
+    std::collections::BTreeMap,
+    serde::{Serialize, Deserialize},
+    crate::encoding::{to_hex, from_hex},
+};
+
+/// The smallest fraction of the currency a block reward can be made of.
+pub const REWARD_PRECISION: f64 = 1e-8;
+
+/// The number of blocks whose median time a new block must be later than.
+pub const MEDIAN_TIME_WINDOW: usize = 11;
+
+/// The number of accounts, with the highest balances, whose balance is exported as a metric.
+const METRICS_TOP_ACCOUNTS: usize = 10;
 
 /// A struct to handle the blockchain of the currency.
 /// 
@@ -11,128 +40,4494 @@ use crate::{
 /// - the chain of `Block`s
 /// - the pending transactions, already validated, waiting to be put in a new block
 /// - the number of transactions per block
+/// - the difficulty of the proof of work, as the number of leading zero bytes the hash of every block must have
+/// - the difficulty the next block is mined with, which is adjusted if a target block time is set
+/// - the target time between blocks, if any, and the number of blocks after which the difficulty is adjusted
+/// - the addresses that took part in the transactions of the chain
+/// - the balance of every address, according to the chain
+/// - the change of the balance of every address caused by the pending transactions
+/// - the rewards of every address that haven't reached the coinbase maturity
+/// - the nonce following the highest nonce used by every address, both in the chain and in the pending transactions
+/// - the index of the block containing every transaction of the chain, by the hash of the transaction
+/// - the address of the miner and the initial reward of the blocks, if a miner is set
+/// - the number of blocks after which the reward is halved (`0` means that the reward is never halved)
+/// - the number of blocks to be put on top of a block before its reward can be spent
+/// - the rewards already collected by every miner
+/// - the index of the last block whose link to the chain was validated
+/// - the public key of the admin, if any, and the hashes of the transactions reversed by the admin
+/// - the identifier of the network the chain belongs to, if any
+/// - the custom rules every new transaction must satisfy
+/// - the `ChainObserver` notified of the events of the chain, if any
 /// 
 /// When the blockchain is created, it comes with the genesis block already put in the chain,
-/// and the genesis is derived from the `Default` implementation of the `Block`.
+/// and the genesis, like the `Default` implementation of the `Block`, has a fixed time,
+/// so every chain with the same difficulty starts with the same genesis block.
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockChain {
     pub index: usize,
     chain: Vec<Block>,
     transactions: Vec<Transaction>,
     transactions_per_block: usize,
+    difficulty: usize,
+    current_difficulty: usize,
+    target_block_time: Option<Duration>,
+    adjustment_interval: usize,
+    known_addresses: HashSet<String>,
+    balances: HashMap<String, f64>,
+    pending_balances: HashMap<String, f64>,
+    immature_rewards: HashMap<String, PositiveF64>,
+    next_nonces: HashMap<String, u64>,
+    pending_nonces: HashMap<String, u64>,
+    transaction_blocks: HashMap<[u8; 64], usize>,
+    miner: Option<String>,
+    initial_reward: PositiveF64,
+    halving_interval: usize,
+    coinbase_maturity: usize,
+    collected_rewards: HashMap<String, PositiveF64>,
+    validated_height: usize,
+    admin: Option<[u8; 32]>,
+    charged_back: HashSet<[u8; 64]>,
+    network_id: Option<String>,
+    rules: ValidationRules,
+    observer: Observer,
+}
+
+/// A custom rule a `Transaction` must satisfy to be put in the chain, in addition to the built-in checks.
+pub type ValidationRule = dyn Fn(&Transaction, &BlockChain) -> Result<(), ValidationError> + Send + Sync;
+
+/// A structure to handle the custom rules of the chain; the rules are shared by the clones of the chain,
+/// and they aren't compared when two chains are compared, since closures can't be compared.
+#[derive(Clone, Default)]
+struct ValidationRules(Vec<Arc<ValidationRule>>);
+
+impl fmt::Debug for ValidationRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ValidationRules({})", self.0.len())
+    }
+}
+
+impl PartialEq for ValidationRules {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// A trait to be notified of the events of a `BlockChain`, so that they can be logged, for example;
+/// every method does nothing by default, so only the events of interest need to be handled.
+pub trait ChainObserver {
+    /// This method is called when a new transaction is validated and put among the pending transactions.
+    fn on_transaction_validated(&self, _transaction: &Transaction) {}
+
+    /// This method is called when a new transaction is rejected, with the `ValidationError` of the transaction.
+    fn on_transaction_rejected(&self, _error: &ValidationError) {}
+
+    /// This method is called when a new block is mined and put in the chain.
+    fn on_block_mined(&self, _block: &Block) {}
+
+    /// This method is called when a new block is mined, but it can't be put in the chain.
+    fn on_block_rejected(&self, _block: &Block, _error: &ChainError) {}
+}
+
+/// A structure to handle the `ChainObserver` of the chain, if any; the observer is shared by the clones of the chain,
+/// and it isn't compared when two chains are compared, since it doesn't change the chain.
+#[derive(Clone, Default)]
+struct Observer(Option<Arc<dyn ChainObserver + Send + Sync>>);
+
+impl Observer {
+    /// This method notifies the observer of an event, if there is an observer.
+    fn notify(&self, event: impl FnOnce(&dyn ChainObserver)) {
+        if let Some(observer) = &self.0 {
+            event(observer.as_ref());
+        }
+    }
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observer({})", if self.0.is_some() { "Some" } else { "None" })
+    }
+}
+
+impl PartialEq for Observer {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
 
 impl BlockChain {
-    /// Generates a new `BlockChain`.
+    /// Generates a new `BlockChain`, whose blocks are mined with the given difficulty:
+    /// the hash of every block must have `difficulty` leading zero bytes, so every additional byte
+    /// makes mining a block about 256 times slower.
     /// 
     /// # Example
     /// ```
     /// # use blockchain::blockchain::BlockChain;
-    /// let blockchain = BlockChain::new(5); // here you can choose the number of transactions per block
+    /// let blockchain = BlockChain::new(5, 1); // here you can choose the number of transactions per block and the difficulty
     /// 
     /// assert_eq!(blockchain.chain().len(), 1); // the blockchain starts with the genesis block
+    /// assert_eq!(blockchain.chain()[0].hash()[0], 0);
+    /// ```
+    /// 
+    /// # Panics
+    /// The difficulty can't be greater than `MAX_DIFFICULTY`, since the genesis block couldn't be mined.
+    /// 
+    /// ```should_panic
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::MAX_DIFFICULTY;
+    /// let blockchain = BlockChain::new(5, MAX_DIFFICULTY + 1); // the program panics instead of mining forever
     /// ```
-    pub fn new(transactions_per_block: usize) -> Self {
-        let genesis_block = Block::default();
+    pub fn new(transactions_per_block: usize, difficulty: usize) -> Self {
+        assert!(difficulty <= MAX_DIFFICULTY, "the difficulty can't be greater than {}", MAX_DIFFICULTY);
+
+        BlockChain::from_genesis(transactions_per_block, Block::genesis(None, difficulty))
+    }
+
+    /// Generates a new `BlockChain` starting with the given genesis block, which can't be removed from the chain,
+    /// so that the genesis can credit some addresses with its `Coinbase`; the blocks of the chain
+    /// are mined with the difficulty of the genesis block.
+    /// 
+    /// The function returns a `Result<BlockChain, ChainError>`, because
+    /// if the index of the genesis block isn't zero, a `ChainError::BrokenLink` error is returned,
+    /// and if the genesis block isn't valid, or its previous hash isn't made of zeros,
+    /// a `ChainError::InvalidBlock` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, ChainError};
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("founder_address", PositiveF64::new(1000.0).unwrap());
+    /// let genesis = Block::with_coinbase(0, [0; 64], Vec::new(), coinbase, DIFFICULTY);
+    /// 
+    /// let blockchain = BlockChain::with_genesis(5, genesis).unwrap();
+    /// 
+    /// assert_eq!(blockchain.balance_of("founder_address").value(), 1000.0);
+    /// assert_eq!(blockchain.difficulty(), DIFFICULTY);
+    /// 
+    /// let misplaced = Block::new(1, [0; 64], Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(BlockChain::with_genesis(5, misplaced), Err(ChainError::BrokenLink(0)));
+    /// ```
+    pub fn with_genesis(transactions_per_block: usize, genesis: Block) -> Result<Self, ChainError> {
+        if genesis.index() != 0 {
+            return Err(ChainError::BrokenLink(0));
+        }
+
+        genesis.validate(&[0; 64]).map_err(|e| ChainError::InvalidBlock(0, e))?;
+
+        let mut blockchain = BlockChain::from_genesis(transactions_per_block, genesis);
+
+        blockchain.rebuild_indexes();
+
+        Ok(blockchain)
+    }
+
+    /// Generates a new `BlockChain` with the genesis block, whose difficulty is used by the chain.
+    fn from_genesis(transactions_per_block: usize, genesis_block: Block) -> Self {
+        let difficulty = genesis_block.difficulty();
 
         Self {
             index: 0,
             chain: vec![genesis_block],
             transactions: Vec::new(),
             transactions_per_block,
+            difficulty,
+            current_difficulty: difficulty,
+            target_block_time: None,
+            adjustment_interval: 0,
+            known_addresses: HashSet::new(),
+            balances: HashMap::new(),
+            pending_balances: HashMap::new(),
+            immature_rewards: HashMap::new(),
+            next_nonces: HashMap::new(),
+            pending_nonces: HashMap::new(),
+            transaction_blocks: HashMap::new(),
+            miner: None,
+            initial_reward: PositiveF64::zero(),
+            halving_interval: 0,
+            coinbase_maturity: 0,
+            collected_rewards: HashMap::new(),
+            validated_height: 0,
+            admin: None,
+            charged_back: HashSet::new(),
+            network_id: None,
+            rules: ValidationRules::default(),
+            observer: Observer::default(),
         }
     }
 
     /// This method creates a transaction with the arguments, and then this transaction is checked:
     /// if it's a valid transaction, it goes into the `Vec<Transaction>` pending transactions vector,
-    /// and the amount is transferred from the sender's `Account` into the receiver's `Account`;
-    /// if the transaction isn't valid, details are provided.
+    /// and the amount is transferred from the sender's `Account` into the receiver's `Account`.
+    /// 
+    /// The function returns a `Result<(), PushError>`, because if the transaction can't be generated,
+    /// isn't valid, or doesn't satisfy the custom rules of the chain, a `PushError::Invalid` error is returned,
+    /// with the `ValidationError` of the transaction, and neither the accounts nor the chain are changed;
+    /// if the sender can't afford the amount on top of its pending transactions, as when a stale copy
+    /// of the account is spent twice, the error is `ValidationError::InvalidAmount`, and if the nonce of the sender
+    /// was already used by a transaction of the chain, the error is `ValidationError::StaleNonce`.
+    /// 
+    /// When the transaction is put in the chain, the nonce of the sender's `Account` is incremented.
+    /// The `ChainObserver` of the chain, if any, is notified both of the validated and of the rejected transactions.
     /// 
     /// When the number of pending transactions is equal to the number of `transactions_per_block`,
-    /// set while creating the blockchain, a new `Block` is generated.
+    /// set while creating the blockchain, a new `Block` is generated, as in `.mine_block()`;
+    /// if the block can't be put in the chain, a `PushError::Chain` error is returned, with the `ChainError`
+    /// of the block, and the transaction, which was accepted, is still pending.
     /// 
     /// # Example
     /// ```
-    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::blockchain::{BlockChain, PushError};
+    /// # use blockchain::transaction::ValidationError;
     /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
     /// let mut alex = Account::new("Alex", "White", "1992#?I_like_Rust92");
     /// let mut bob = Account::new("Bob", "Reds", "sUpEr_SeCuRe_PaSsWoRd#+!789");
-    /// alex.add_money(100.0); // alex must have enough money to perform the transaction!
+    /// alex.add_money(100.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY); // the number of transactions per block is set to 1
+    /// blockchain.premine(vec![(alex.address(), PositiveF64::new(100.0).unwrap())]).unwrap(); // alex must have enough money on the chain to perform the transaction!
+    /// 
+    /// assert_eq!(blockchain.push_transaction(&mut alex, &mut bob, 50.0, "wrong_password"), Err(PushError::Invalid(ValidationError::WrongPassword)));
+    /// assert_eq!(blockchain.index, 0);
     /// 
-    /// let mut blockchain = BlockChain::new(1); // the number of transactions per block is set to 1
-    /// blockchain.push_transaction(&mut alex, &mut bob, 50.0, "1992#?I_like_Rust92"); // the chain is going to have two blocks, the first one being the genesis block
+    /// blockchain.push_transaction(&mut alex, &mut bob, 50.0, "1992#?I_like_Rust92").unwrap(); // the chain is going to have two blocks, the first one being the genesis block
     /// 
     /// assert_eq!(blockchain.index, 1); // the genesis block has index #0
     /// ```
-    pub fn push_transaction(&mut self, sender: &mut Account, receiver: &mut Account, amount: f64, sender_password: &str) {
-        let transaction = Transaction::new(sender.clone(), receiver.clone(), amount, sender_password);
+    pub fn push_transaction(&mut self, sender: &mut Account, receiver: &mut Account, amount: f64, sender_password: &str) -> Result<(), PushError> {
+        let transaction = match Transaction::new(sender.clone(), receiver.clone(), amount, sender_password) {
+            Ok(transaction) => transaction,
+            Err(e) => return Err(self.reject(e)),
+        };
+
+        self.submit_transaction(sender, receiver, transaction)
+    }
+
+    /// This method puts a transaction already generated by the sender, for example with a `TransactionBuilder`
+    /// to set its fee, its memo or its `Condition`, in the chain, as `.push_transaction()`: the transaction
+    /// goes through the same checks, and if it's valid, the amount and the fee are taken from the sender's `Account`,
+    /// the amount is given to the receiver's `Account`, and a new `Block` is generated when it's needed.
+    /// 
+    /// The function returns a `Result<(), PushError>`, with the same errors of `.push_transaction()`;
+    /// moreover, if the transaction isn't sent from the sender's `Account` to the receiver's `Account`,
+    /// a `PushError::Invalid` error is returned, with `ValidationError::InvalidSignature`,
+    /// and if its `Condition` isn't met in the next block, as in `Transaction::validate_conditional()`,
+    /// the error is `ValidationError::ConditionNotMet`; since the chain can't carry the preimage
+    /// of a `Condition::HashLock` yet, the hash-locked transactions are always refused.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::transaction::TransactionBuilder;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut donna = Account::new("Donna", "Noble", "donna_noble_2008");
+    /// let mut wilf = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// donna.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(2, DIFFICULTY);
+    /// blockchain.premine(vec![(donna.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// 
+    /// let transaction = TransactionBuilder::new(donna.clone(), wilf.clone(), 4.0, "donna_noble_2008")
+    ///     .fee(0.5)
+    ///     .build()
+    ///     .unwrap();
+    /// 
+    /// blockchain.submit_transaction(&mut donna, &mut wilf, transaction).unwrap();
+    /// 
+    /// assert_eq!(blockchain.pending_transactions().len(), 1);
+    /// assert_eq!(donna.balance(), 5.5);
+    /// assert_eq!(wilf.balance(), 4.0);
+    /// ```
+    pub fn submit_transaction(&mut self, sender: &mut Account, receiver: &mut Account, transaction: Transaction) -> Result<(), PushError> {
+        if let Err(e) = self.accept_transaction(sender, receiver, &transaction) {
+            return Err(self.reject(e));
+        }
 
-        println!("Validating transaction...");
+        sender.increment_nonce();
 
-        match transaction.validate(transaction.hash()) {
-            Ok(_) => {
-                self.transactions.push(transaction);
+        self.observer.notify(|observer| observer.on_transaction_validated(&transaction));
 
-                // the amount is checked in the validation of the transaction
-                unsafe {
-                    sender.sub_money_unchecked(amount);
-                    receiver.add_money_unchecked(amount);
-                }
+        self.index_pending(&transaction);
+        self.transactions.push(transaction);
 
-                println!("validated!");
-            },
-            Err(e) => match e {
-                ValidationError::Tempered => eprintln!("{} Details: transaction from {} to {}, for an amount of {}, resulted to be tempered.",
-                    e,
-                    transaction.sender,
-                    transaction.receiver,
-                    transaction.amount(),
-                ),
-                ValidationError::WrongPassword => eprintln!("{} Details: the sender's password is not correct.", e),
-                ValidationError::InvalidSignature => eprintln!("{} Details: transaction from {} to {}, for an amount of {}, wasn't validated because of invalid signature.",
-                    e,
-                    transaction.sender,
-                    transaction.receiver,
-                    transaction.amount(),
-                ),
-                ValidationError::InvalidAmount => eprintln!("{} Details: transaction from {} to {}, for an amount of {}, wasn't validated because of an invalid amount.",
-                    e,
-                    transaction.sender,
-                    transaction.receiver,
-                    transaction.amount(),
-                ),
+        if self.transactions.len() >= self.transactions_per_block {
+            self.mine_block().map_err(PushError::Chain)?;
+        }
+
+        Ok(())
+    }
+
+    /// This method notifies the `ChainObserver` of the chain, if any, of a rejected transaction,
+    /// and returns the error of the transaction as a `PushError`.
+    fn reject(&self, error: ValidationError) -> PushError {
+        self.observer.notify(|observer| observer.on_transaction_rejected(&error));
+
+        PushError::Invalid(error)
+    }
+
+    /// This method checks the transaction of `.submit_transaction()`, and then takes the amount and the fee
+    /// from the sender's `Account`, and gives the amount to the receiver's `Account`.
+    fn accept_transaction(&self, sender: &mut Account, receiver: &mut Account, transaction: &Transaction) -> Result<(), ValidationError> {
+        if transaction.sender.address() != sender.address() || transaction.receiver.address() != receiver.address() {
+            return Err(ValidationError::InvalidSignature);
+        }
+
+        self.check_transaction(transaction)?;
+
+        let debit = transaction.amount() + transaction.fee();
+
+        sender.sub_money(debit).map_err(|_| ValidationError::InvalidAmount)?;
+
+        if receiver.add_money(transaction.amount()).is_err() {
+            // the debit was just subtracted, so adding it back can't fail
+            sender.add_money(debit).unwrap();
+
+            return Err(ValidationError::InvalidAmount);
+        }
+
+        Ok(())
+    }
+
+    /// This method checks a new transaction against the chain: the transaction must be valid, its `Condition` must be met
+    /// in the block to be put on top of the chain, it must satisfy the custom rules of the chain,
+    /// be affordable by the sender on top of its pending transactions, and carry a nonce which wasn't used yet.
+    /// 
+    /// The chain can't carry the preimage of a `Condition::HashLock` yet, so the hash-locked transactions are refused.
+    fn check_transaction(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        transaction.validate_conditional(transaction.hash(), self.index + 1, None)?;
+
+        self.check_rules(transaction)?;
+
+        self.check_pending_debits(transaction)?;
+
+        self.check_nonce(transaction)
+    }
+
+    /// This method sets the `ChainObserver` of the chain, which is notified of the new transactions,
+    /// validated or rejected, and of the new blocks mined; the chain doesn't log anything by itself.
+    /// The observer must be `Send` and `Sync`, so that the chain can still be shared between threads.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, ChainObserver};
+    /// # use blockchain::transaction::ValidationError;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// struct Logger;
+    /// 
+    /// impl ChainObserver for Logger {
+    ///     fn on_transaction_rejected(&self, error: &ValidationError) {
+    ///         eprintln!("{}", error);
+    ///     }
+    /// 
+    ///     fn on_block_mined(&self, block: &Block) {
+    ///         println!("{}", block);
+    ///     }
+    /// }
+    /// 
+    /// let mut martha = Account::new("Martha", "Jones", "MarthaJ_2007");
+    /// let mut tish = Account::new("Tish", "Jones", "tish_jones_2007");
+    /// martha.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(martha.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_observer(Box::new(Logger));
+    /// 
+    /// blockchain.push_transaction(&mut martha, &mut tish, 4.0, "MarthaJ_2007").unwrap(); // the new block is printed
+    /// ```
+    pub fn set_observer(&mut self, observer: Box<dyn ChainObserver + Send + Sync>) {
+        self.observer = Observer(Some(Arc::from(observer)));
+    }
+
+    /// This method registers a custom rule, which every new transaction must satisfy to be put in the chain,
+    /// in addition to the built-in checks of `Transaction::validate()`; the rules are checked
+    /// in the order they were added, and the error of the first rule which isn't satisfied is returned.
+    /// As the `ChainObserver`, the rules must be `Send` and `Sync`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, PushError};
+    /// # use blockchain::transaction::ValidationError;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let mut ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(100.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.add_validator(Box::new(|transaction, _| {
+    ///     if transaction.amount() >= 1.0 { Ok(()) } else { Err(ValidationError::InvalidAmount) }
+    /// }));
+    /// 
+    /// assert_eq!(blockchain.push_transaction(&mut yaz, &mut ryan, 0.5, "yaz_khan_2018"), Err(PushError::Invalid(ValidationError::InvalidAmount)));
+    /// 
+    /// assert!(blockchain.pending_for(&yaz.address()).is_empty());
+    /// ```
+    pub fn add_validator(&mut self, rule: Box<ValidationRule>) {
+        self.rules.0.push(Arc::from(rule));
+    }
+
+    /// This method checks that the sender of the transaction can afford it on top of its pending transactions,
+    /// according to the chain: the balance of the sender in the chain, changed by its pending transactions,
+    /// and without the rewards that haven't reached the coinbase maturity, must cover the amount and the fee
+    /// of the transaction; the balance of the sender's `Account` isn't trusted.
+    fn check_pending_debits(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        if transaction.amount() + transaction.fee() > self.available_balance(&transaction.sender.address()) {
+            Err(ValidationError::InvalidAmount)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// This method returns the money the address can spend according to the chain: its balance in the chain,
+    /// changed by its pending transactions, without the rewards that haven't reached the coinbase maturity.
+    fn available_balance(&self, address: &str) -> f64 {
+        let chain = self.balances.get(address).copied().unwrap_or(0.0);
+        let pending = self.pending_balances.get(address).copied().unwrap_or(0.0);
+
+        chain + pending - self.immature_rewards(address)
+    }
+
+    /// This method returns the lowest nonce the next transaction sent by the address can carry,
+    /// which follows the highest nonce of the transactions sent by the address,
+    /// both in the chain and pending; the reversals of the chargebacks aren't counted,
+    /// since they aren't sent by the address itself, but the transactions they reversed are,
+    /// so that a reversed transaction can't be replayed.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut missy = Account::new("Missy", "Master", "missy_master_2014");
+    /// let mut nardole = Account::new("Nardole", "Nardole", "nardole_2015");
+    /// missy.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(missy.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// 
+    /// assert_eq!(blockchain.next_nonce(&missy.address()), 0);
+    /// 
+    /// blockchain.push_transaction(&mut missy, &mut nardole, 4.0, "missy_master_2014").unwrap();
+    /// 
+    /// assert_eq!(blockchain.next_nonce(&missy.address()), missy.nonce());
+    /// ```
+    pub fn next_nonce(&self, address: &str) -> u64 {
+        let chain = self.next_nonces.get(address).copied().unwrap_or(0);
+        let pending = self.pending_nonces.get(address).copied().unwrap_or(0);
+
+        chain.max(pending)
+    }
+
+    /// This method checks that the nonce of the transaction wasn't already used by the sender.
+    fn check_nonce(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        if transaction.nonce() < self.next_nonce(&transaction.sender.address()) {
+            Err(ValidationError::StaleNonce)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// This method checks the custom rules of the chain against the transaction.
+    fn check_rules(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        self.rules.0.iter().try_for_each(|rule| rule(transaction, self))
+    }
+
+    /// This method moves the pending transactions into a new `Block`, mined with the current difficulty
+    /// and rewarding the miner, if any, which is then put in the chain; the new block is returned.
+    /// The pending transactions whose `Condition` isn't met in the new block stay pending.
+    /// 
+    /// It's called by `.push_transaction()` when the number of pending transactions reaches
+    /// the number of `transactions_per_block`, but it can be called at any time, even without pending transactions.
+    /// 
+    /// The function returns a `Result<&Block, ChainError>`, because if the block can't be put in the chain,
+    /// for example if its time isn't later than the time of the last block, the `ChainError` is returned,
+    /// and the transactions are pending again.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut donna = Account::new("Donna", "Noble", "donna_noble_2008");
+    /// let mut wilf = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// donna.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(donna.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut donna, &mut wilf, 4.0, "donna_noble_2008").unwrap(); // still pending
+    /// 
+    /// assert_eq!(blockchain.mine_block().unwrap().transactions().len(), 1);
+    /// assert!(blockchain.pending_transactions().is_empty());
+    /// assert_eq!(blockchain.chain().len(), 2);
+    /// ```
+    pub fn mine_block(&mut self) -> Result<&Block, ChainError> {
+        let transactions = self.take_spendable();
+        let new_block = self.next_block(transactions);
+
+        if let Err(e) = self.check_link(&new_block) {
+            self.observer.notify(|observer| observer.on_block_rejected(&new_block, &e));
+
+            self.restore_pending(new_block.into_transactions());
+
+            return Err(e);
+        }
+
+        self.push_mined_block(new_block);
+        self.rebuild_pending_index();
+
+        Ok(self.last_block())
+    }
+
+    /// This method checks that the `Condition` of the pending transaction is met in the block
+    /// to be put on top of the chain, as in `Transaction::validate_conditional()`.
+    fn is_spendable(&self, transaction: &Transaction) -> bool {
+        transaction.validate_conditional(transaction.hash(), self.index + 1, None).is_ok()
+    }
+
+    /// This method takes the pending transactions which can be spent in the block to be put on top of the chain,
+    /// and leaves pending the ones which must still wait, for example because the chain was rolled back
+    /// after they were accepted.
+    fn take_spendable(&mut self) -> Vec<Transaction> {
+        let (spendable, waiting) = mem::take(&mut self.transactions)
+            .into_iter()
+            .partition(|transaction| self.is_spendable(transaction));
+
+        self.transactions = waiting;
+
+        spendable
+    }
+
+    /// This method puts the transactions of a block which couldn't be put in the chain back among the pending transactions,
+    /// before the ones which were still waiting.
+    fn restore_pending(&mut self, mut transactions: Vec<Transaction>) {
+        transactions.append(&mut self.transactions);
+
+        self.transactions = transactions;
+    }
+
+    /// This method puts the pending transactions in a new `Block` right away, even if their number
+    /// is lower than the number of `transactions_per_block`, and returns the new block;
+    /// if there are no pending transactions, or the block can't be put in the chain, `None` is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut amy = Account::new("Amy", "Pond", "amy_pond_1989");
+    /// let mut rory = Account::new("Rory", "Williams", "rory_the_roman");
+    /// amy.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// blockchain.premine(vec![(amy.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// 
+    /// assert!(blockchain.flush().is_none());
+    /// 
+    /// blockchain.push_transaction(&mut amy, &mut rory, 4.0, "amy_pond_1989").unwrap();
+    /// 
+    /// assert_eq!(blockchain.flush().unwrap().transactions().len(), 1);
+    /// ```
+    pub fn flush(&mut self) -> Option<&Block> {
+        if self.transactions.is_empty() {
+            return None;
+        }
+
+        self.mine_block().ok()
+    }
+
+    /// This method puts the pending transactions in a new `Block`, as when the number of `transactions_per_block`
+    /// is reached, but the reward of the block is split between the addresses of the payouts,
+    /// as in a mining pool; the shares of the payouts must sum to the reward of the block
+    /// plus the fees of the pending transactions.
+    /// 
+    /// The function returns a `Result<(), PayoutError>`, because
+    /// if a share is negative or not finite, or the shares overflow, a `PayoutError::InvalidShare` error is returned,
+    /// if the shares don't sum to the reward, a `PayoutError::Mismatch` error is returned,
+    /// and if the new block can't be linked to the chain, a `PayoutError::Chain` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.set_miner("pool_address", 50.0).unwrap();
+    /// 
+    /// let payouts = [(String::from("first_miner"), 30.0), (String::from("second_miner"), 20.0)];
+    /// 
+    /// assert!(blockchain.seal_block_with_payouts(&payouts).is_ok());
+    /// assert_eq!(blockchain.chain()[1].coinbase().unwrap().reward_of("second_miner"), 20.0);
+    /// ```
+    pub fn seal_block_with_payouts(&mut self, payouts: &[(String, f64)]) -> Result<(), PayoutError> {
+        let reward = self.coinbase_amount(self.transactions.iter().filter(|transaction| self.is_spendable(transaction)));
+
+        let payouts = payouts
+            .iter()
+            .map(|(miner, share)| Ok((miner.clone(), PositiveF64::new(*share).map_err(|_| PayoutError::InvalidShare)?)))
+            .collect::<Result<Vec<(String, PositiveF64)>, PayoutError>>()?;
+
+        let total = payouts
+            .iter()
+            .try_fold(PositiveF64::zero(), |total, (_, share)| total.checked_add(*share))
+            .map_err(|_| PayoutError::InvalidShare)?;
+
+        if (total.value() - reward.value()).abs() > REWARD_PRECISION {
+            return Err(PayoutError::Mismatch {
+                expected: reward.value(),
+                actual: total.value(),
+            });
+        }
+
+        let new_block = Block::with_coinbase(
+            self.index + 1,
+            self.last_block().hash(),
+            self.take_spendable(),
+            Coinbase::with_payouts(payouts),
+            self.current_difficulty,
+        );
+
+        if let Err(e) = self.check_link(&new_block) {
+            self.restore_pending(new_block.into_transactions());
+
+            return Err(PayoutError::Chain(e));
+        }
+
+        self.push_mined_block(new_block);
+        self.rebuild_pending_index();
+
+        Ok(())
+    }
+
+    /// This method generates the block to be put on top of the chain with the transactions,
+    /// rewarding the miner, if any.
+    fn next_block(&self, transactions: Vec<Transaction>) -> Block {
+        let prev_hash = self.last_block().hash();
+
+        match &self.miner {
+            Some(miner) => {
+                let coinbase = Coinbase::new(miner, self.coinbase_amount(&transactions));
+
+                Block::with_coinbase(self.index + 1, prev_hash, transactions, coinbase, self.current_difficulty)
             },
-        };
+            None => Block::new(self.index + 1, prev_hash, transactions, self.current_difficulty),
+        }
+    }
+
+    /// This method returns the amount credited by the `Coinbase` of the block to be put on top of the chain
+    /// with the transactions, which is the reward of the block plus the fees of the transactions.
+    fn coinbase_amount<'a>(&self, transactions: impl IntoIterator<Item = &'a Transaction>) -> PositiveF64 {
+        let fees: f64 = transactions.into_iter().map(|t| t.fee()).sum();
+
+        self.block_reward(self.index + 1) + PositiveF64::new(fees).unwrap_or(PositiveF64::zero())
+    }
+
+    /// This method puts a block mined by the chain, whose link has already been checked, in the chain,
+    /// and notifies the `ChainObserver` of the chain, if any.
+    fn push_mined_block(&mut self, block: Block) {
+        self.observer.notify(|observer| observer.on_block_mined(&block));
+
+        self.push_block(block);
+    }
+
+    /// This method puts a block, whose link has already been checked, in the chain,
+    /// and moves the validated height to it, if the previous blocks were all validated.
+    fn push_block(&mut self, block: Block) {
+        if self.validated_height == self.index {
+            self.validated_height += 1;
+        }
 
-        if self.transactions.len() == self.transactions_per_block {
-            self.index += 1;
+        self.index += 1;
 
-            println!("Validating block...");
+        self.index_block(&block);
 
-            let new_block = Block::new(
-                self.index,
-                self.chain.last().unwrap().hash(),
-                self.transactions.clone()
-            );
+        self.chain.push(block);
 
-            self.chain.push(new_block);
+        self.index_matured_block();
 
-            self.transactions.clear();
+        self.current_difficulty = self.adjusted_difficulty(self.current_difficulty, self.index);
+    }
 
-            println!("validated!");
+    /// This method puts a block received from another node in the chain:
+    /// since the blocks up to the validated height are already trusted,
+    /// only the link between the new block and the last block is checked.
+    /// 
+    /// If the index of the block doesn't follow the index of the last block,
+    /// or the link isn't valid, the block isn't put in the chain and a `ChainError` is returned,
+    /// as in `.is_valid()`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// let block = Block::new(1, blockchain.chain()[0].hash(), Vec::new(), DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.append_block(block.clone()), Ok(()));
+    /// assert!(blockchain.append_block(block).is_err()); // the block is already in the chain
+    /// assert_eq!(blockchain.validated_height(), 1);
+    /// ```
+    pub fn append_block(&mut self, block: Block) -> Result<(), ChainError> {
+        if block.index() != self.index + 1 {
+            return Err(ChainError::BrokenLink(block.index()));
         }
+
+        self.check_link(&block)?;
+
+        self.push_block(block);
+
+        Ok(())
     }
-    
-    /// This method returns the `chain` of the blockchain, since this field isn't `pub`.
+
+    /// This method returns the index of the last block whose link to the chain was validated,
+    /// since the `validated_height` field isn't `pub`.
     /// 
     /// # Example
     /// ```
     /// # use blockchain::blockchain::BlockChain;
-    /// let blockchain = BlockChain::new(8);
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
     /// 
-    /// assert_eq!(blockchain.chain().len(), 1); // the blockchain starts with the genesis block
+    /// assert_eq!(blockchain.validated_height(), 0); // the genesis block is always trusted
     /// ```
-    pub fn chain(&self) -> Vec<Block> {
-        self.chain.clone()
+    pub fn validated_height(&self) -> usize {
+        self.validated_height
+    }
+
+    /// This method removes the last `n` blocks from the chain, and returns them;
+    /// the balances, the reversed transactions and the difficulty of the next blocks
+    /// are computed again from the remaining blocks, and the validated height is lowered if it was above the new last block.
+    /// 
+    /// The pending transactions and the `Account`s aren't changed.
+    /// If `n` is more than the number of blocks after the genesis block,
+    /// nothing is removed and a `ChainError::InvalidRollback` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, ChainError};
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.append_block(Block::new(1, blockchain.chain()[0].hash(), Vec::new(), DIFFICULTY)).unwrap();
+    /// 
+    /// assert_eq!(blockchain.rollback(2), Err(ChainError::InvalidRollback(2))); // the genesis block can't be removed
+    /// assert_eq!(blockchain.rollback(1).unwrap().len(), 1);
+    /// assert_eq!(blockchain.index, 0);
+    /// ```
+    pub fn rollback(&mut self, n: usize) -> Result<Vec<Block>, ChainError> {
+        if n > self.index {
+            return Err(ChainError::InvalidRollback(n));
+        }
+
+        let removed = self.chain.split_off(self.chain.len() - n);
+
+        self.index -= n;
+        self.validated_height = self.validated_height.min(self.index);
+
+        self.rebuild_indexes();
+        self.rebuild_difficulty();
+
+        Ok(removed)
+    }
+
+    /// This method computes again the difficulty of the next block from the blocks of the chain,
+    /// adjusting it at every block as in `.set_target_block_time()`.
+    fn rebuild_difficulty(&mut self) {
+        self.current_difficulty = (1..=self.index).fold(self.difficulty, |difficulty, index| self.adjusted_difficulty(difficulty, index));
+    }
+
+    /// This method sets the public key of the admin, who can reverse the transactions in the chain
+    /// with `.chargeback()`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let admin = Account::new("Kate", "Stewart", "UNIT_Kate_Stewart");
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.set_admin(admin.public_key());
+    /// ```
+    pub fn set_admin(&mut self, admin: PublicKey) {
+        self.admin = Some(admin.to_bytes());
+    }
+
+    /// This method reverses a transaction in the chain, putting in a new `Block` a transaction
+    /// which sends the same amount back from the receiver to the sender, so that no block is changed;
+    /// the chargeback must be authorized with the secret key of the admin, set with `.set_admin()`,
+    /// which signs the reversal in place of the receiver.
+    /// 
+    /// Only the balances known by the chain are restored, the `Account`s aren't changed,
+    /// so the receiver can't spend the reversed amount anymore, even if its `Account` still holds it;
+    /// the pending transactions stay pending.
+    /// 
+    /// The function returns a `Result<(), ChargebackError>`, because
+    /// if the secret key isn't the one of the admin, a `ChargebackError::Unauthorized` error is returned,
+    /// if the transaction isn't in the chain, a `ChargebackError::UnknownTransaction` error is returned,
+    /// if the transaction was already reversed, or it's a reversal itself,
+    /// a `ChargebackError::AlreadyReversed` error is returned, and if the receiver can't afford the reversal,
+    /// according to the chain and its pending transactions, a `ChargebackError::InsufficientFunds` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, ChargebackError};
+    /// # use blockchain::account::Account;
+    /// # use std::convert::TryInto;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let admin = Account::new("Kate", "Stewart", "UNIT_Kate_Stewart");
+    /// let mut jackie = Account::new("Jackie", "Tyler", "Jackie_Tyler_2005");
+    /// let mut elton = Account::new("Elton", "Pope", "LINDA_Elton_Pope");
+    /// jackie.add_money(30.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(jackie.address(), PositiveF64::new(30.0).unwrap())]).unwrap();
+    /// blockchain.set_admin(admin.public_key());
+    /// blockchain.push_transaction(&mut jackie, &mut elton, 10.0, "Jackie_Tyler_2005").unwrap();
+    /// 
+    /// let hash = blockchain.transactions_above(0.0)[0].1.hash();
+    /// 
+    /// assert_eq!(blockchain.chargeback(&hash, &jackie.keypair()[..32].try_into().unwrap()), Err(ChargebackError::Unauthorized));
+    /// assert_eq!(blockchain.chargeback(&hash, &admin.keypair()[..32].try_into().unwrap()), Ok(()));
+    /// assert_eq!(blockchain.chain().len(), 3); // the reversal is in a new block
+    /// ```
+    pub fn chargeback(&mut self, tx_hash: &[u8; 64], admin_key: &[u8; 32]) -> Result<(), ChargebackError> {
+        let admin = SecretKey::from_bytes(admin_key)
+            .ok()
+            .map(|secret| Keypair { public: PublicKey::from(&secret), secret })
+            .filter(|admin| Some(admin.public.to_bytes()) == self.admin)
+            .ok_or(ChargebackError::Unauthorized)?;
+
+        if self.charged_back.contains(tx_hash) {
+            return Err(ChargebackError::AlreadyReversed);
+        }
+
+        let transaction = self
+            .find_transaction(tx_hash)
+            .ok_or(ChargebackError::UnknownTransaction)?;
+
+        if self.available_balance(&transaction.receiver.address()) < transaction.amount() {
+            return Err(ChargebackError::InsufficientFunds);
+        }
+
+        let reversal = transaction.reversed(&admin);
+
+        let new_block = self.next_block(vec![reversal]);
+
+        self.check_link(&new_block).map_err(ChargebackError::Chain)?;
+
+        self.push_mined_block(new_block);
+
+        Ok(())
+    }
+
+    /// This function computes the hash of a block, as in the proof of work, for the given duration,
+    /// and returns the number of hashes computed per second, so that the difficulty can be tuned for the machine.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use std::time::Duration;
+    /// let hashrate = BlockChain::benchmark_hashrate(Duration::from_millis(100));
+    /// 
+    /// assert!(hashrate > 0.0);
+    /// ```
+    pub fn benchmark_hashrate(duration: Duration) -> f64 {
+        let time = Utc::now();
+        let start = Instant::now();
+
+        let mut hashes: u128 = 0;
+
+        while start.elapsed() < duration {
+            Block::hash_fields(0, [0; 64], &[0; 64], None, time, 0, hashes);
+
+            hashes += 1;
+        }
+
+        hashes as f64 / start.elapsed().as_secs_f64()
+    }
+
+    /// This method computes again the indexes of the chain from its blocks: the addresses, the balances,
+    /// the immature rewards and the nonces known by the chain, and the index of the block containing every transaction;
+    /// it's used to recover the consistency of the indexes after the blocks were changed.
+    /// The changes of the balances caused by the pending transactions are computed again too.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut leela = Account::new("Leela", "Sevateem", "leela_janis_thorn");
+    /// let mut k9 = Account::new("K9", "Mark III", "Affirmative_Master");
+    /// leela.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(leela.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut leela, &mut k9, 3.0, "leela_janis_thorn").unwrap();
+    /// 
+    /// let root = blockchain.state_merkle_root();
+    /// 
+    /// blockchain.rebuild_indexes();
+    /// 
+    /// assert_eq!(blockchain.state_merkle_root(), root);
+    /// ```
+    pub fn rebuild_indexes(&mut self) {
+        self.known_addresses.clear();
+        self.balances.clear();
+        self.immature_rewards.clear();
+        self.next_nonces.clear();
+        self.transaction_blocks.clear();
+        self.charged_back.clear();
+
+        let chain = std::mem::take(&mut self.chain);
+
+        for block in &chain {
+            self.index_block(block);
+        }
+
+        self.chain = chain;
+
+        self.rebuild_pending_index();
+    }
+
+    /// This method computes again the changes of the balances and the nonces caused by the pending transactions.
+    fn rebuild_pending_index(&mut self) {
+        self.pending_balances.clear();
+        self.pending_nonces.clear();
+
+        let transactions = mem::take(&mut self.transactions);
+
+        for transaction in &transactions {
+            self.index_pending(transaction);
+        }
+
+        self.transactions = transactions;
+    }
+
+    /// This method updates the changes of the balances and the nonces caused by the pending transactions with the transaction.
+    fn index_pending(&mut self, transaction: &Transaction) {
+        *self.pending_balances.entry(transaction.sender.address()).or_insert(0.0) -= transaction.amount() + transaction.fee();
+        *self.pending_balances.entry(transaction.receiver.address()).or_insert(0.0) += transaction.amount();
+
+        if transaction.reversal_of().is_none() {
+            BlockChain::index_nonce(&mut self.pending_nonces, transaction);
+        }
+    }
+
+    /// This method raises the next nonce of the sender of the transaction in the index, if the nonce of the transaction is higher.
+    fn index_nonce(nonces: &mut HashMap<String, u64>, transaction: &Transaction) {
+        let next_nonce = nonces.entry(transaction.sender.address()).or_insert(0);
+
+        *next_nonce = (*next_nonce).max(transaction.nonce() + 1);
+    }
+
+    /// This method updates the indexes of the chain with the transactions and the `Coinbase` of the block.
+    fn index_block(&mut self, block: &Block) {
+        for transaction in block.transactions() {
+            self.transaction_blocks.entry(transaction.hash()).or_insert(block.index());
+
+            let sender = transaction.sender.address();
+            let receiver = transaction.receiver.address();
+
+            *self.balances.entry(sender.clone()).or_insert(0.0) -= transaction.amount() + transaction.fee();
+            *self.balances.entry(receiver.clone()).or_insert(0.0) += transaction.amount();
+
+            self.known_addresses.insert(sender);
+            self.known_addresses.insert(receiver);
+
+            match transaction.reversal_of() {
+                Some(original) => {
+                    self.charged_back.insert(original);
+                    self.charged_back.insert(transaction.hash());
+                },
+                None => BlockChain::index_nonce(&mut self.next_nonces, transaction),
+            }
+        }
+
+        if let Some(coinbase) = block.coinbase() {
+            let is_mature = self.is_mature(block);
+
+            for (miner, reward) in coinbase.payouts() {
+                *self.balances.entry(miner.clone()).or_insert(0.0) += reward.value();
+
+                if !is_mature {
+                    *self.immature_rewards.entry(miner.clone()).or_insert_with(PositiveF64::zero) += *reward;
+                }
+            }
+        }
+    }
+
+    /// This method removes from the immature rewards the rewards of the block
+    /// which has just reached the coinbase maturity, after a new block was put in the chain.
+    fn index_matured_block(&mut self) {
+        let height = match self.index.checked_sub(self.coinbase_maturity) {
+            Some(height) if height > 0 && self.coinbase_maturity > 0 => height,
+            _ => return,
+        };
+
+        if let Some(coinbase) = self.chain[height].coinbase() {
+            for (miner, reward) in coinbase.payouts() {
+                if let Some(immature) = self.immature_rewards.get_mut(miner) {
+                    *immature = immature.saturating_sub(*reward);
+                }
+            }
+        }
+    }
+
+    /// This method returns the transaction in the chain with the given hash, if any,
+    /// looking up the block containing it in the index of the chain.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut adric = Account::new("Adric", "Alzarius", "adric_maths_badge");
+    /// let mut nyssa = Account::new("Nyssa", "Traken", "nyssa_of_traken");
+    /// adric.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(adric.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut adric, &mut nyssa, 3.0, "adric_maths_badge").unwrap();
+    /// 
+    /// let hash = blockchain.transactions_above(0.0)[0].1.hash();
+    /// 
+    /// assert_eq!(blockchain.find_transaction(&hash).unwrap().amount(), 3.0);
+    /// assert!(blockchain.find_transaction(&[0; 64]).is_none());
+    /// ```
+    pub fn find_transaction(&self, hash: &[u8; 64]) -> Option<&Transaction> {
+        let index = *self.transaction_blocks.get(hash)?;
+
+        self.chain
+            .get(index)?
+            .transactions()
+            .iter()
+            .find(|t| t.hash() == *hash)
+    }
+
+    /// This method checks that the `prev_hash` of the block is equal to the hash
+    /// of the block before it in the chain, recomputed from its fields.
+    /// 
+    /// If the block is the first one after the genesis block,
+    /// a `ChainError::BrokenGenesisLink` error is returned, otherwise a `ChainError::BrokenLink` error is returned;
+    /// if the block is older than the block before it, a `ChainError::NonMonotonicTime` error is returned,
+    /// and if it isn't later than the median time of the `MEDIAN_TIME_WINDOW` blocks before it,
+    /// a `ChainError::BelowMedianTime` error is returned.
+    fn check_link(&self, block: &Block) -> Result<(), ChainError> {
+        BlockChain::check_link_to(&self.chain[..block.index()], block)
+    }
+
+    /// This function checks the link between the block and the blocks before it, as `.check_link()`.
+    fn check_link_to(prev_blocks: &[Block], block: &Block) -> Result<(), ChainError> {
+        let prev_block = &prev_blocks[prev_blocks.len() - 1];
+
+        if block.prev_hash() != prev_block.compute_hash() {
+            if block.index() == 1 {
+                Err(ChainError::BrokenGenesisLink)
+            } else {
+                Err(ChainError::BrokenLink(block.index()))
+            }
+        } else if block.time() < prev_block.time() {
+            Err(ChainError::NonMonotonicTime(block.index()))
+        } else if BlockChain::median_time(prev_blocks, MEDIAN_TIME_WINDOW).is_some_and(|median| block.time() <= median) {
+            Err(ChainError::BelowMedianTime(block.index()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// This method returns the median time, as seconds since the Unix epoch, of the last `window` blocks of the chain,
+    /// or of the whole chain if it has less than `window` blocks; a new block must be later than
+    /// the median time of the `MEDIAN_TIME_WINDOW` blocks before it, so that its time can't be set in the past
+    /// by a single miner.
+    /// 
+    /// If the window contains an even number of blocks, the later of the two middle times is taken,
+    /// and if the window is empty, `None` is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.median_time_past(11), Some(blockchain.chain()[0].time().timestamp() as u64));
+    /// assert_eq!(blockchain.median_time_past(0), None);
+    /// ```
+    pub fn median_time_past(&self, window: usize) -> Option<u64> {
+        BlockChain::median_time(&self.chain, window).map(|median| median.timestamp() as u64)
+    }
+
+    /// This function returns the median time of the last `window` blocks.
+    fn median_time(blocks: &[Block], window: usize) -> Option<DateTime<Utc>> {
+        let mut times: Vec<DateTime<Utc>> = blocks
+            .iter()
+            .rev()
+            .take(window)
+            .map(|block| block.time())
+            .collect();
+
+        times.sort();
+
+        times.get(times.len() / 2).copied()
+    }
+
+    /// This method checks if the blocks of the chain are correctly linked to each other,
+    /// and returns a `Err(ChainError)` if the chain isn't valid.
+    /// 
+    /// - If the `prev_hash` of block #1 doesn't match with the hash of the genesis block,
+    ///   a `ChainError::BrokenGenesisLink` error is returned.
+    /// - If the `prev_hash` of any other block doesn't match with the hash of the previous block,
+    ///   a `ChainError::BrokenLink` error is returned.
+    /// - If the time of a block is earlier than the time of the previous block,
+    ///   a `ChainError::NonMonotonicTime` error is returned.
+    /// - If the time of a block isn't later than the median time of the `MEDIAN_TIME_WINDOW` blocks before it,
+    ///   a `ChainError::BelowMedianTime` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut oscar = Account::new("Oscar", "Perry", "oscar_perry_7777");
+    /// let mut paula = Account::new("Paula", "Quinn", "PaulaQ#2019");
+    /// oscar.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(oscar.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut oscar, &mut paula, 5.0, "oscar_perry_7777").unwrap();
+    /// 
+    /// assert_eq!(blockchain.is_valid(), Ok(()));
+    /// ```
+    pub fn is_valid(&self) -> Result<(), ChainError> {
+        self.chain
+            .iter()
+            .skip(1)
+            .try_for_each(|block| self.check_link(block))
+    }
+
+    /// This method checks the whole chain, block by block, so that any tampering
+    /// after the blocks were mined is detected, and returns a `Err(ChainError)` pinpointing
+    /// the first block which isn't valid.
+    /// 
+    /// - If the index of a block doesn't match with its position in the chain,
+    ///   a `ChainError::BrokenLink` error is returned.
+    /// - If a block doesn't pass `Block::validate()` against the hash of the block before it,
+    ///   or it was mined with a difficulty lower than the difficulty of the chain,
+    ///   a `ChainError::InvalidBlock` error is returned, with the `BlockError` of the block.
+    /// - If a transaction was already put in a previous block, a `ChainError::InvalidBlock` error
+    ///   is returned, with a `BlockError::DuplicateTransaction` error.
+    /// - If the time of a block isn't valid, the same errors of `.is_valid()` are returned.
+    /// - If a transaction of a block doesn't pass `Transaction::validate_conditional()` at the index of the block,
+    ///   a `ChainError::InvalidTransaction` error is returned, with the `ValidationError` of the transaction;
+    ///   if its sender can't afford its amount and its fee, according to the blocks before it,
+    ///   the error is `ValidationError::InvalidAmount`.
+    /// - If the `Coinbase` of a block, other than the genesis block, doesn't credit exactly the reward of the block,
+    ///   as returned by `.block_reward()`, plus the fees of its transactions, a `ChainError::InvalidCoinbase` error
+    ///   is returned; a block without a `Coinbase` is valid, and its fees aren't credited to anyone.
+    /// - If the reversal of a chargeback isn't signed by the admin of the chain, a `ChainError::InvalidTransaction`
+    ///   error is returned, with a `ValidationError::InvalidSignature` error; if the reversed transaction isn't in a previous block,
+    ///   doesn't match with the reversal, or was already reversed, a `ChainError::InvalidReversal` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut ace = Account::new("Dorothy", "McShane", "Nitro-9_Ace");
+    /// let mut mel = Account::new("Melanie", "Bush", "mel_bush_1986");
+    /// ace.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(ace.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut ace, &mut mel, 5.0, "Nitro-9_Ace").unwrap();
+    /// 
+    /// assert_eq!(blockchain.validate_chain(), Ok(()));
+    /// ```
+    pub fn validate_chain(&self) -> Result<(), ChainError> {
+        self.validate_blocks(&self.chain)
+    }
+
+    /// This method checks that the `Coinbase` of the given mined block, if any, is equal to the reward of the block,
+    /// as returned by `.block_reward()`, plus the fees of its transactions.
+    fn has_valid_coinbase(&self, block: &Block) -> bool {
+        block.coinbase().map_or(true, |coinbase| {
+            let fees: f64 = block.transactions().iter().map(|t| t.fee()).sum();
+            let expected = self.block_reward(block.index()).value() + fees;
+
+            (coinbase.reward() - expected).abs() <= REWARD_PRECISION
+        })
+    }
+
+    /// This method checks the blocks as `.validate_chain()`, with the difficulty and the admin of the chain;
+    /// the index of every block must also match with its position.
+    fn validate_blocks(&self, blocks: &[Block]) -> Result<(), ChainError> {
+        let admin = self.admin.and_then(|admin| PublicKey::from_bytes(&admin).ok());
+
+        let mut seen: HashMap<[u8; 64], &Transaction> = HashMap::new();
+        let mut reversed = HashSet::new();
+        let mut balances: HashMap<String, f64> = HashMap::new();
+
+        for (position, block) in blocks.iter().enumerate() {
+            let index = block.index();
+            let prev_hash = match position {
+                0 => [0; 64],
+                _ => blocks[position - 1].hash(),
+            };
+
+            if index != position {
+                return Err(ChainError::BrokenLink(position));
+            }
+
+            block.validate(&prev_hash).map_err(|e| ChainError::InvalidBlock(index, e))?;
+
+            if block.difficulty() < self.difficulty {
+                return Err(ChainError::InvalidBlock(index, BlockError::InvalidProofOfWork));
+            }
+
+            if position > 0 {
+                BlockChain::check_link_to(&blocks[..position], block)?;
+            }
+
+            for transaction in block.transactions() {
+                let sender = transaction.sender.address();
+
+                match (transaction.reversal_of(), &admin) {
+                    (None, _) => transaction.validate_conditional(transaction.hash(), index, None),
+                    (Some(_), Some(admin)) => transaction.validate_reversal(transaction.hash(), admin),
+                    (Some(_), None) => Err(ValidationError::InvalidSignature),
+                }
+                .map_err(|e| ChainError::InvalidTransaction(index, e))?;
+
+                if let Some(original) = transaction.reversal_of() {
+                    let is_reversal = seen.get(&original).is_some_and(|original| {
+                        original.reversal_of().is_none()
+                            && original.sender.address() == transaction.receiver.address()
+                            && original.receiver.address() == sender
+                            && original.amount() == transaction.amount()
+                    });
+
+                    if !is_reversal || !reversed.insert(original) {
+                        return Err(ChainError::InvalidReversal(index));
+                    }
+                }
+
+                if balances.get(&sender).copied().unwrap_or(0.0) < transaction.amount() + transaction.fee() {
+                    return Err(ChainError::InvalidTransaction(index, ValidationError::InvalidAmount));
+                }
+
+                if seen.insert(transaction.hash(), transaction).is_some() {
+                    return Err(ChainError::InvalidBlock(index, BlockError::DuplicateTransaction));
+                }
+
+                *balances.entry(sender).or_insert(0.0) -= transaction.amount() + transaction.fee();
+                *balances.entry(transaction.receiver.address()).or_insert(0.0) += transaction.amount();
+            }
+
+            if position > 0 && !self.has_valid_coinbase(block) {
+                return Err(ChainError::InvalidCoinbase(index));
+            }
+
+            if let Some(coinbase) = block.coinbase() {
+                for (miner, reward) in coinbase.payouts() {
+                    *balances.entry(miner.clone()).or_insert(0.0) += reward.value();
+                }
+            }
+        }
+
+        Ok(())
+    }
+    
+    /// This method replaces the chain with the chain of another node, following the longest chain rule:
+    /// the other chain is adopted only if it's valid and strictly longer than the chain, and `Ok(true)` is returned,
+    /// otherwise the chain isn't changed and `Ok(false)` is returned.
+    /// 
+    /// The other chain is checked as in `.validate_chain()`, with the difficulty of the chain,
+    /// and its genesis block must be the same genesis block of the chain;
+    /// if it isn't valid, the `ChainError` is returned, and a different genesis block
+    /// is reported as a `ChainError::BrokenGenesisLink` error.
+    /// 
+    /// The pending transactions already put in the other chain are removed.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut grace = Account::new("Grace", "O'Brien", "grace_obrien_2018");
+    /// let mut graham = Account::new("Graham", "O'Brien", "graham_obrien_2018");
+    /// grace.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(grace.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// let mut peer = BlockChain::new(1, DIFFICULTY);
+    /// peer.premine(vec![(grace.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// peer.push_transaction(&mut grace, &mut graham, 4.0, "grace_obrien_2018").unwrap();
+    /// 
+    /// assert_eq!(blockchain.replace_chain(peer.chain()), Ok(true));
+    /// assert_eq!(blockchain.chain().len(), 2);
+    /// assert_eq!(blockchain.replace_chain(peer.chain()), Ok(false)); // not strictly longer
+    /// ```
+    pub fn replace_chain(&mut self, other: Vec<Block>) -> Result<bool, ChainError> {
+        if other.first().map(|genesis| genesis.hash()) != Some(self.chain[0].hash()) {
+            return Err(ChainError::BrokenGenesisLink);
+        }
+
+        self.validate_blocks(&other)?;
+
+        if other.len() <= self.len() {
+            return Ok(false);
+        }
+
+        self.chain = other;
+        self.index = self.chain.len() - 1;
+        self.validated_height = self.index;
+
+        self.rebuild_indexes();
+        self.rebuild_difficulty();
+
+        let transaction_blocks = &self.transaction_blocks;
+
+        self.transactions.retain(|t| !transaction_blocks.contains_key(&t.hash()));
+
+        self.rebuild_pending_index();
+
+        Ok(true)
+    }
+
+    /// This method returns the `chain` of the blockchain, since this field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(8, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.chain().len(), 1); // the blockchain starts with the genesis block
+    /// ```
+    pub fn chain(&self) -> Vec<Block> {
+        self.chain.clone()
+    }
+
+    /// This method returns an iterator over the blocks of the chain, starting from the genesis block,
+    /// without cloning them as `.chain()` does; `&BlockChain` can also be iterated directly.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut jo = Account::new("Jo", "Grant", "jo_grant_1971");
+    /// let mut mike = Account::new("Mike", "Yates", "captain_yates_1971");
+    /// jo.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(jo.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut jo, &mut mike, 4.0, "jo_grant_1971").unwrap();
+    /// 
+    /// let indexes: Vec<usize> = blockchain.iter().map(|block| block.index()).collect();
+    /// 
+    /// assert_eq!(indexes, [0, 1]);
+    /// assert_eq!((&blockchain).into_iter().count(), 2);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        self.chain.iter()
+    }
+
+    /// This method returns the number of blocks of the chain, counting the genesis block,
+    /// without cloning them as `.chain()` does.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.len(), 1); // the blockchain starts with the genesis block
+    /// ```
+    pub fn len(&self) -> usize {
+        self.chain.len()
+    }
+
+    /// This method checks if the chain has no blocks, which never happens,
+    /// since every chain starts with the genesis block.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert!(!blockchain.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.chain.is_empty()
+    }
+
+    /// This method returns the last block of the chain, which is the genesis block
+    /// if no block has been mined yet.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut liz = Account::new("Liz", "Shaw", "liz_shaw_1970");
+    /// let mut benton = Account::new("John", "Benton", "sergeant_benton");
+    /// liz.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.last_block(), &Block::default());
+    /// 
+    /// blockchain.premine(vec![(liz.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut liz, &mut benton, 4.0, "liz_shaw_1970").unwrap();
+    /// 
+    /// assert_eq!(blockchain.last_block().index(), 1);
+    /// ```
+    pub fn last_block(&self) -> &Block {
+        self.chain.last().expect("The chain always contains the genesis block.")
+    }
+
+    /// This method returns the block of the chain with the given index, if any, without cloning it.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(8, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.get_block(0).unwrap().index(), 0); // the genesis block
+    /// assert!(blockchain.get_block(1).is_none());
+    /// ```
+    pub fn get_block(&self, index: usize) -> Option<&Block> {
+        self.chain.get(index)
+    }
+
+    /// This method returns the block of the chain with the given hash, if any, without cloning it.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(8, DIFFICULTY);
+    /// let hash = blockchain.get_block(0).unwrap().hash();
+    /// 
+    /// assert_eq!(blockchain.block_by_hash(&hash).unwrap().index(), 0);
+    /// assert!(blockchain.block_by_hash(&[0; 64]).is_none());
+    /// ```
+    pub fn block_by_hash(&self, hash: &[u8; 64]) -> Option<&Block> {
+        self.chain.iter().find(|block| block.hash() == *hash)
+    }
+
+    /// This method returns the number of distinct addresses that have sent or received money
+    /// in the transactions of the chain; the pending transactions aren't counted.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut ann = Account::new("Ann", "Moore", "AnnMoore_1985!");
+    /// let mut ben = Account::new("Ben", "Nash", "B3n_N4sh#");
+    /// let mut cal = Account::new("Cal", "Owen", "cal.owen.pass");
+    /// ann.add_money(20.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(2, DIFFICULTY);
+    /// blockchain.premine(vec![(ann.address(), PositiveF64::new(20.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut ann, &mut ben, 10.0, "AnnMoore_1985!").unwrap();
+    /// 
+    /// assert_eq!(blockchain.unique_participants(), 0); // the transaction is still pending
+    /// 
+    /// blockchain.push_transaction(&mut ben, &mut cal, 5.0, "B3n_N4sh#").unwrap();
+    /// 
+    /// assert_eq!(blockchain.unique_participants(), 3); // ben is counted only once
+    /// ```
+    pub fn unique_participants(&self) -> usize {
+        self.known_addresses.len()
+    }
+
+    /// This method generates a `BalanceProof` for the address, containing the transactions
+    /// of the chain involving the address and the rewards paid to it, so that a light client knowing only
+    /// the hashes of the blocks can check the balance of the address; the pending transactions aren't included.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut amy = Account::new("Amy", "Pond", "amy_pond_1989");
+    /// let mut rory = Account::new("Rory", "Williams", "R0ry_W1lliams");
+    /// amy.add_money(50.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(amy.address(), PositiveF64::new(50.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut amy, &mut rory, 20.0, "amy_pond_1989").unwrap();
+    /// 
+    /// let headers: Vec<[u8; 64]> = blockchain.iter().map(|b| b.hash()).collect();
+    /// 
+    /// assert_eq!(blockchain.balance_proof(&amy.address()).verify(&headers), Some(30.0)); // the premine is counted too
+    /// assert_eq!(blockchain.balance_proof(&rory.address()).verify(&headers), Some(20.0));
+    /// ```
+    pub fn balance_proof(&self, address: &str) -> BalanceProof {
+        BalanceProof::new(address, &self.chain)
+    }
+
+    /// This method sets the address of the miner, which is rewarded with the `reward`,
+    /// plus the fees of the transactions of the block, in the `Coinbase` of every new block.
+    /// 
+    /// The reward is part of the reward schedule, which every `Coinbase` of the chain is checked against,
+    /// so it can't be changed once a block is put on top of the genesis block, while the miner can.
+    /// 
+    /// The function returns a `Result<(), RewardError>`, because
+    /// if the reward is negative, a `RewardError::InvalidReward` error is returned,
+    /// and if the reward is changed after the first block, a `RewardError::FrozenSchedule` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, RewardError};
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::{Block, DIFFICULTY};
+    /// let miner = Account::new("Donna", "Noble", "donna_noble_1978");
+    /// let other_miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// 
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// 
+    /// assert!(blockchain.set_miner(&miner.address(), 50.0).is_ok());
+    /// assert!(blockchain.set_miner(&miner.address(), -50.0).is_err());
+    /// 
+    /// blockchain.mine_block().unwrap();
+    /// 
+    /// assert_eq!(blockchain.set_miner(&miner.address(), 25.0), Err(RewardError::FrozenSchedule));
+    /// assert!(blockchain.set_miner(&other_miner.address(), 50.0).is_ok());
+    /// ```
+    pub fn set_miner(&mut self, address: &str, reward: f64) -> Result<(), RewardError> {
+        let reward = PositiveF64::new(reward).map_err(RewardError::InvalidReward)?;
+
+        if self.index > 0 && reward != self.initial_reward {
+            return Err(RewardError::FrozenSchedule);
+        }
+
+        self.initial_reward = reward;
+        self.miner = Some(String::from(address));
+
+        Ok(())
+    }
+
+    /// This method returns the number of transactions per block, set while creating the blockchain.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(3, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.transactions_per_block(), 3);
+    /// ```
+    pub fn transactions_per_block(&self) -> usize {
+        self.transactions_per_block
+    }
+
+    /// This method returns the difficulty of the proof of work, set while creating the blockchain,
+    /// as the number of leading zero bytes the hash of every block must have.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// let blockchain = BlockChain::new(3, 1);
+    /// 
+    /// assert_eq!(blockchain.difficulty(), 1);
+    /// ```
+    pub fn difficulty(&self) -> usize {
+        self.difficulty
+    }
+
+    /// This method returns the difficulty the next block is mined with, which is the difficulty of the chain,
+    /// unless it was adjusted after a target block time was set with `.set_target_block_time()`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// let blockchain = BlockChain::new(3, 1);
+    /// 
+    /// assert_eq!(blockchain.current_difficulty(), 1);
+    /// ```
+    pub fn current_difficulty(&self) -> usize {
+        self.current_difficulty
+    }
+
+    /// This method sets the target time between blocks: every `adjustment_interval` blocks,
+    /// the time spent to mine the last `adjustment_interval` blocks is compared with the target,
+    /// and the difficulty of the next blocks is raised by one byte if they were mined faster,
+    /// or lowered by one byte if they were mined slower, but never below the difficulty of the chain
+    /// and never above `MAX_DIFFICULTY`;
+    /// if the interval is `0`, the difficulty is never adjusted.
+    /// 
+    /// The genesis block isn't counted, since its time is fixed, so the first adjustment
+    /// happens after `2 * adjustment_interval` blocks.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use std::time::Duration;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut kate = Account::new("Kate", "Lethbridge-Stewart", "UNIT_Kate_Stewart");
+    /// let mut osgood = Account::new("Petronella", "Osgood", "Osgood_Scarf_2013");
+    /// kate.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, 0);
+    /// blockchain.premine(vec![(kate.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_target_block_time(Duration::from_secs(600), 1);
+    /// 
+    /// blockchain.push_transaction(&mut kate, &mut osgood, 1.0, "UNIT_Kate_Stewart").unwrap();
+    /// blockchain.push_transaction(&mut kate, &mut osgood, 1.0, "UNIT_Kate_Stewart").unwrap();
+    /// 
+    /// assert_eq!(blockchain.current_difficulty(), 1); // the block was mined in much less than 10 minutes
+    /// ```
+    pub fn set_target_block_time(&mut self, target_block_time: Duration, adjustment_interval: usize) {
+        self.target_block_time = Some(target_block_time);
+        self.adjustment_interval = adjustment_interval;
+    }
+
+    /// This method returns the difficulty of the blocks after the block with the given index,
+    /// adjusting the difficulty of the previous blocks as described in `.set_target_block_time()`,
+    /// when the index is a multiple of the adjustment interval.
+    fn adjusted_difficulty(&self, difficulty: usize, index: usize) -> usize {
+        let interval = self.adjustment_interval;
+
+        let target_block_time = match self.target_block_time {
+            Some(target_block_time) if index > interval && index % interval == 0 => target_block_time,
+            _ => return difficulty,
+        };
+
+        let target_span = interval
+            .try_into()
+            .ok()
+            .and_then(|interval| target_block_time.checked_mul(interval))
+            .unwrap_or(Duration::MAX);
+
+        // a negative span can't be converted, and it's treated as a span of zero
+        let span = (self.chain[index].time() - self.chain[index - interval].time())
+            .to_std()
+            .unwrap_or(Duration::ZERO);
+
+        if span < target_span {
+            (difficulty + 1).min(MAX_DIFFICULTY)
+        } else if span > target_span {
+            difficulty.saturating_sub(1).max(self.difficulty)
+        } else {
+            difficulty
+        }
+    }
+
+    /// This method sets the identifier of the network the chain belongs to,
+    /// so that chains of different networks can be told apart.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.network_id(), None);
+    /// 
+    /// blockchain.set_network_id("testnet");
+    /// 
+    /// assert_eq!(blockchain.network_id(), Some("testnet"));
+    /// ```
+    pub fn set_network_id(&mut self, network_id: &str) {
+        self.network_id = Some(String::from(network_id));
+    }
+
+    /// This method returns the identifier of the network the chain belongs to, if any,
+    /// since the `network_id` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(3, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.network_id(), None);
+    /// ```
+    pub fn network_id(&self) -> Option<&str> {
+        self.network_id.as_deref()
+    }
+
+    /// This method replaces the genesis block with one whose `Coinbase` credits the allocations,
+    /// so that the money is issued before any block is mined; the `Account`s keep their own balances,
+    /// which are debited by `.push_transaction()`, so the money must be added to them as well.
+    /// 
+    /// The function returns a `Result<(), ChainError>`, because the genesis block can be replaced
+    /// only while the chain contains the genesis block alone, otherwise the next block wouldn't follow it,
+    /// and a `ChainError::LatePremine` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, ChainError};
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut rose = Account::new("Rose", "Tyler", "bad_wolf_2005");
+    /// let mut mickey = Account::new("Mickey", "Smith", "rickey_with_an_m");
+    /// rose.add_money(10.0).unwrap(); // the balance of her account, which the chain doesn't trust
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(rose.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// 
+    /// assert_eq!(blockchain.balance_of(&rose.address()).value(), 10.0);
+    /// 
+    /// blockchain.push_transaction(&mut rose, &mut mickey, 4.0, "bad_wolf_2005").unwrap();
+    /// 
+    /// assert_eq!(blockchain.premine(Vec::new()), Err(ChainError::LatePremine));
+    /// ```
+    pub fn premine(&mut self, allocations: Vec<(String, PositiveF64)>) -> Result<(), ChainError> {
+        if self.index > 0 {
+            return Err(ChainError::LatePremine);
+        }
+
+        self.chain[0] = Block::genesis(Some(Coinbase::with_payouts(allocations)), self.difficulty);
+
+        self.rebuild_indexes();
+
+        Ok(())
+    }
+
+    /// This method sets the number of blocks after which the reward of the miner is halved;
+    /// if the interval is `0`, the reward is never halved.
+    /// 
+    /// As the reward, the interval is part of the reward schedule, so it can't be changed
+    /// once a block is put on top of the genesis block, and a `RewardError::FrozenSchedule` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, RewardError};
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// blockchain.set_miner("miner_address", 50.0).unwrap();
+    /// 
+    /// blockchain.set_halving_interval(210_000).unwrap();
+    /// 
+    /// assert_eq!(blockchain.block_reward(210_000).value(), 25.0);
+    /// 
+    /// blockchain.mine_block().unwrap();
+    /// 
+    /// assert_eq!(blockchain.set_halving_interval(10), Err(RewardError::FrozenSchedule));
+    /// ```
+    pub fn set_halving_interval(&mut self, halving_interval: usize) -> Result<(), RewardError> {
+        if self.index > 0 && halving_interval != self.halving_interval {
+            return Err(RewardError::FrozenSchedule);
+        }
+
+        self.halving_interval = halving_interval;
+
+        Ok(())
+    }
+
+    /// This method returns the reward of the miner of the block at the given height:
+    /// the initial reward, set with `.set_miner()`, is halved every `halving_interval` blocks,
+    /// and it's rounded down to `REWARD_PRECISION`, so that it eventually becomes zero.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// blockchain.set_miner("miner_address", 50.0).unwrap();
+    /// blockchain.set_halving_interval(10).unwrap();
+    /// 
+    /// assert_eq!(blockchain.block_reward(9).value(), 50.0);
+    /// assert_eq!(blockchain.block_reward(10).value(), 25.0);
+    /// assert_eq!(blockchain.block_reward(25).value(), 12.5);
+    /// ```
+    pub fn block_reward(&self, height: usize) -> PositiveF64 {
+        if self.halving_interval == 0 {
+            return self.initial_reward;
+        }
+
+        let halvings = (height / self.halving_interval).try_into().unwrap_or(i32::MAX);
+        let reward = self.initial_reward.value() * 0.5_f64.powi(halvings);
+
+        PositiveF64::new((reward / REWARD_PRECISION).floor() * REWARD_PRECISION).unwrap()
+    }
+
+    /// This method sets the coinbase maturity, which is the number of blocks
+    /// that must be put in the chain on top of a block before its reward can be spent;
+    /// the rewards that can't be spent yet are computed again with the new maturity.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(3, DIFFICULTY);
+    /// 
+    /// blockchain.set_coinbase_maturity(100);
+    /// ```
+    pub fn set_coinbase_maturity(&mut self, coinbase_maturity: usize) {
+        self.coinbase_maturity = coinbase_maturity;
+
+        self.rebuild_indexes();
+    }
+
+    /// This method checks if the reward of the block can be spent, which means that
+    /// at least `coinbase_maturity` blocks have been put in the chain on top of it;
+    /// the `Coinbase` of the genesis block isn't a reward but the premine, so it can always be spent.
+    fn is_mature(&self, block: &Block) -> bool {
+        block.index() == 0 || self.index - block.index() >= self.coinbase_maturity
+    }
+
+    /// This method returns the sum of the rewards of the blocks mined by the address
+    /// that reached the coinbase maturity.
+    fn mature_rewards_of(&self, address: &str) -> PositiveF64 {
+        self.chain
+            .iter()
+            .filter(|block| self.is_mature(block))
+            .filter_map(|block| block.coinbase())
+            .filter(|coinbase| coinbase.pays(address))
+            .map(|coinbase| PositiveF64::new(coinbase.reward_of(address)).unwrap())
+            .sum()
+    }
+
+    /// This method returns the rewards of the address that can't be spent yet,
+    /// because their blocks haven't reached the coinbase maturity.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut martha = Account::new("Martha", "Jones", "martha_jones_2007");
+    /// let mut jack = Account::new("Jack", "Harkness", "Capt41n_Jack");
+    /// let miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// martha.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(martha.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_miner(&miner.address(), 50.0).unwrap();
+    /// blockchain.set_coinbase_maturity(10);
+    /// 
+    /// blockchain.push_transaction(&mut martha, &mut jack, 5.0, "martha_jones_2007").unwrap();
+    /// 
+    /// assert_eq!(blockchain.immature_rewards(&miner.address()), 50.0);
+    /// ```
+    pub fn immature_rewards(&self, address: &str) -> f64 {
+        self.immature_rewards
+            .get(address)
+            .map_or(0.0, |rewards| rewards.value())
+    }
+
+    /// This method returns the rewards of the address that can be spent, which are the rewards
+    /// of the blocks that reached the coinbase maturity, minus the rewards already collected.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut martha = Account::new("Martha", "Jones", "martha_jones_2007");
+    /// let mut jack = Account::new("Jack", "Harkness", "Capt41n_Jack");
+    /// let miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// martha.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(martha.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_miner(&miner.address(), 50.0).unwrap(); // the coinbase maturity is 0 by default
+    /// 
+    /// blockchain.push_transaction(&mut martha, &mut jack, 5.0, "martha_jones_2007").unwrap();
+    /// 
+    /// assert_eq!(blockchain.spendable_rewards(&miner.address()), 50.0);
+    /// ```
+    pub fn spendable_rewards(&self, address: &str) -> f64 {
+        let collected = self.collected_rewards
+            .get(address)
+            .copied()
+            .unwrap_or_else(PositiveF64::zero);
+
+        self.mature_rewards_of(address).saturating_sub(collected).value()
+    }
+
+    /// This method moves the spendable rewards of the miner into the balance of its `Account`,
+    /// and returns the amount collected; the rewards that haven't reached the coinbase maturity
+    /// can't be collected.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut martha = Account::new("Martha", "Jones", "martha_jones_2007");
+    /// let mut jack = Account::new("Jack", "Harkness", "Capt41n_Jack");
+    /// let mut miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// martha.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(martha.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_miner(&miner.address(), 50.0).unwrap();
+    /// 
+    /// blockchain.push_transaction(&mut martha, &mut jack, 5.0, "martha_jones_2007").unwrap();
+    /// 
+    /// assert_eq!(blockchain.collect_rewards(&mut miner), 50.0);
+    /// assert_eq!(blockchain.collect_rewards(&mut miner), 0.0); // the rewards can be collected only once
+    /// assert_eq!(miner.balance(), 50.0);
+    /// ```
+    pub fn collect_rewards(&mut self, miner: &mut Account) -> f64 {
+        let address = miner.address();
+        let amount = self.spendable_rewards(&address);
+
+        if miner.add_money(amount).is_ok() {
+            let collected = self.collected_rewards
+                .entry(address)
+                .or_insert_with(PositiveF64::zero);
+
+            *collected += PositiveF64::new(amount).unwrap();
+        }
+
+        amount
+    }
+
+    /// This method returns the blocks of the chain whose `Coinbase` rewarded the address.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut jo = Account::new("Jo", "Grant", "jo_grant_1971");
+    /// let mut mike = Account::new("Mike", "Yates", "Capt_Mike_Yates");
+    /// let first_miner = Account::new("Liz", "Shaw", "liz_shaw_1970");
+    /// let second_miner = Account::new("Alistair", "Lethbridge", "Brigadier#UNIT");
+    /// jo.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(jo.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// 
+    /// blockchain.set_miner(&first_miner.address(), 50.0).unwrap();
+    /// blockchain.push_transaction(&mut jo, &mut mike, 1.0, "jo_grant_1971").unwrap();
+    /// 
+    /// blockchain.set_miner(&second_miner.address(), 50.0).unwrap();
+    /// blockchain.push_transaction(&mut jo, &mut mike, 2.0, "jo_grant_1971").unwrap();
+    /// 
+    /// let first_blocks = blockchain.blocks_by_miner(&first_miner.address());
+    /// let second_blocks = blockchain.blocks_by_miner(&second_miner.address());
+    /// 
+    /// assert_eq!(first_blocks.len(), 1);
+    /// assert_eq!(first_blocks[0].index(), 1);
+    /// assert_eq!(second_blocks.len(), 1);
+    /// assert_eq!(second_blocks[0].index(), 2);
+    /// assert!(blockchain.blocks_by_miner(&mike.address()).is_empty());
+    /// ```
+    pub fn blocks_by_miner(&self, address: &str) -> Vec<&Block> {
+        self.chain
+            .iter()
+            .filter(|block| block.coinbase().is_some_and(|coinbase| coinbase.pays(address)))
+            .collect()
+    }
+
+    /// This method returns the time, as seconds since the Unix epoch, of the most recent block
+    /// containing a transaction sent or received by the address, so that dormant accounts can be found.
+    /// 
+    /// If the address never took part in a transaction of the chain, `None` is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut polly = Account::new("Polly", "Wright", "polly_wright_1966");
+    /// let mut ben = Account::new("Ben", "Jackson", "able_seaman_ben");
+    /// let jamie = Account::new("Jamie", "McCrimmon", "jamie_piper_1746");
+    /// polly.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(polly.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut polly, &mut ben, 2.0, "polly_wright_1966").unwrap();
+    /// 
+    /// assert_eq!(blockchain.last_activity(&ben.address()), Some(blockchain.chain()[1].time().timestamp() as u64));
+    /// assert_eq!(blockchain.last_activity(&jamie.address()), None);
+    /// ```
+    pub fn last_activity(&self, address: &str) -> Option<u64> {
+        self.chain
+            .iter()
+            .rev()
+            .find(|block| {
+                block
+                    .transactions()
+                    .iter()
+                    .any(|t| t.sender.address() == address || t.receiver.address() == address)
+            })
+            .map(|block| block.time().timestamp() as u64)
+    }
+
+    /// This method returns the balance of the address, according to the chain, so that the chain
+    /// is the source of truth instead of the `Account`s: every transaction of the chain, and every pending transaction,
+    /// credits the receiver with the amount and debits the sender with the amount and the fee,
+    /// and the `Coinbase` of every block credits its payouts.
+    /// 
+    /// The money added to an `Account` outside of the chain isn't counted,
+    /// so if the address spent more than it received in the chain, the balance is zero.
+    /// 
+    /// The balance is read from the indexes of the chain, which are updated as the blocks
+    /// and the pending transactions are put in the chain, so the chain isn't scanned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut jamie = Account::new("Jamie", "McCrimmon", "jamie_pipes_1746");
+    /// let mut polly = Account::new("Polly", "Wright", "polly_wright_1966");
+    /// jamie.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(jamie.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut jamie, &mut polly, 4.0, "jamie_pipes_1746").unwrap(); // still pending
+    /// 
+    /// assert_eq!(blockchain.balance_of(&polly.address()).value(), 4.0);
+    /// assert_eq!(blockchain.balance_of(&jamie.address()).value(), 6.0);
+    /// ```
+    pub fn balance_of(&self, address: &str) -> PositiveF64 {
+        let chain = self.balances.get(address).copied().unwrap_or(0.0);
+        let pending = self.pending_balances.get(address).copied().unwrap_or(0.0);
+
+        PositiveF64::new(chain + pending).unwrap_or(PositiveF64::zero())
+    }
+
+    /// This method returns the balance of the address, according to the chain, counting only the blocks
+    /// with at least `min_confirmations` confirmations, since the most recent blocks may still be replaced.
+    /// 
+    /// The last block of the chain has one confirmation, the block before it has two, and so on;
+    /// both the transactions and the `Coinbase` of the blocks are counted.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut zoe = Account::new("Zoe", "Heriot", "zoe_heriot_wheel");
+    /// let mut victoria = Account::new("Victoria", "Waterfield", "victoria_1866");
+    /// zoe.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(zoe.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut zoe, &mut victoria, 4.0, "zoe_heriot_wheel").unwrap();
+    /// 
+    /// assert_eq!(blockchain.confirmed_balance_of(&victoria.address(), 1), 4.0);
+    /// assert_eq!(blockchain.confirmed_balance_of(&victoria.address(), 2), 0.0);
+    /// ```
+    pub fn confirmed_balance_of(&self, address: &str, min_confirmations: usize) -> f64 {
+        self.chain
+            .iter()
+            .filter(|block| self.index - block.index() + 1 >= min_confirmations)
+            .map(|block| {
+                let transactions = block
+                    .transactions()
+                    .iter()
+                    .map(|t| BlockChain::balance_change(t, address))
+                    .sum::<f64>();
+
+                transactions + block.coinbase().map_or(0.0, |coinbase| coinbase.reward_of(address))
+            })
+            .sum()
+    }
+
+    /// This function returns the change of the balance of the address caused by the transaction:
+    /// the receiver is credited with the amount, and the sender is debited with the amount and the fee.
+    fn balance_change(transaction: &Transaction, address: &str) -> f64 {
+        let mut change = 0.0;
+
+        if transaction.receiver.address() == address {
+            change += transaction.amount();
+        }
+
+        if transaction.sender.address() == address {
+            change -= transaction.amount() + transaction.fee();
+        }
+
+        change
+    }
+
+    /// This method returns a deterministic encoding of the whole chain as bytes, which is the same
+    /// on every platform, so that two chains can be compared, or committed to, by hashing it.
+    /// 
+    /// The number of blocks is written first, followed by every block in order, with:
+    /// - the index, the previous hash and the number of transactions
+    /// - the hashes of the transactions, which commit to all their fields
+    /// - the payouts of the `Coinbase`, if any
+    /// - the nonce, the time and the hash of the block
+    /// 
+    /// Every number is written in little-endian order, and every string is prefixed with its length.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// let copy = blockchain.clone();
+    /// 
+    /// assert_eq!(blockchain.canonical_bytes(), copy.canonical_bytes());
+    /// assert_eq!(blockchain.canonical_bytes(), BlockChain::new(1, DIFFICULTY).canonical_bytes()); // the genesis block is always the same
+    /// assert_ne!(blockchain.canonical_bytes(), BlockChain::new(1, 1).canonical_bytes());
+    /// ```
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+
+        writer.u64(self.chain.len() as u64);
+
+        for block in &self.chain {
+            block.encode(&mut writer);
+        }
+
+        writer.bytes()
+    }
+
+    /// This method returns the root of the Merkle tree built over the balances of the addresses, according to the chain,
+    /// so that two nodes can check that they agree on the state of the chain by comparing only the roots.
+    /// 
+    /// The leaves of the tree are the SHA-512 hashes of the (address, balance) pairs, sorted by address;
+    /// the addresses whose balance is zero are skipped, so the root only depends on the balances.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut ace = Account::new("Dorothy", "McShane", "ace_nitro_9");
+    /// let mut mel = Account::new("Melanie", "Bush", "mel_bush_carrot");
+    /// ace.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.state_merkle_root(), [0; 64]); // no balance yet
+    /// 
+    /// blockchain.premine(vec![(ace.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut ace, &mut mel, 4.0, "ace_nitro_9").unwrap();
+    /// 
+    /// assert_ne!(blockchain.state_merkle_root(), [0; 64]);
+    /// ```
+    pub fn state_merkle_root(&self) -> [u8; 64] {
+        let mut balances: Vec<(&String, &f64)> = self.balances
+            .iter()
+            .filter(|(_, balance)| **balance != 0.0)
+            .collect();
+
+        balances.sort_by(|a, b| a.0.cmp(b.0));
+
+        let leaves: Vec<Vec<u8>> = balances
+            .into_iter()
+            .map(|(address, balance)| {
+                let mut writer = Writer::default();
+
+                writer.string(address);
+                writer.f64(*balance);
+
+                writer.bytes()
+            })
+            .collect();
+
+        merkle::merkle_root(&leaves)
+    }
+
+    /// This method returns the transactions in the chain whose amount is more than the given amount,
+    /// together with the index of the `Block` they're in.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut river = Account::new("River", "Song", "Spoilers_Sweetie");
+    /// let mut rory = Account::new("Rory", "Williams", "the_last_centurion");
+    /// river.add_money(100.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(river.address(), PositiveF64::new(100.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut river, &mut rory, 80.0, "Spoilers_Sweetie").unwrap();
+    /// 
+    /// let large = blockchain.transactions_above(50.0);
+    /// 
+    /// assert_eq!(large.len(), 1);
+    /// assert_eq!(large[0].0, 1);
+    /// assert_eq!(large[0].1.amount(), 80.0);
+    /// ```
+    pub fn transactions_above(&self, amount: f64) -> Vec<(usize, &Transaction)> {
+        self.chain
+            .iter()
+            .flat_map(|block| block.transactions().iter().map(move |t| (block.index(), t)))
+            .filter(|(_, t)| t.amount() > amount)
+            .collect()
+    }
+
+    /// This method returns the hashes of the transactions that appear more than once in the chain,
+    /// which should never happen in a well-formed chain.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut vislor = Account::new("Vislor", "Turlough", "Turlough_Trion");
+    /// let mut tegan = Account::new("Tegan", "Jovanka", "Tegan_Brisbane_81");
+    /// vislor.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(vislor.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut vislor, &mut tegan, 4.0, "Turlough_Trion").unwrap();
+    /// 
+    /// assert!(blockchain.find_duplicates().is_empty());
+    /// ```
+    pub fn find_duplicates(&self) -> Vec<[u8; 64]> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+
+        for transaction in self.chain.iter().flat_map(|block| block.transactions()) {
+            let hash = transaction.hash();
+
+            if !seen.insert(hash) && !duplicates.contains(&hash) {
+                duplicates.push(hash);
+            }
+        }
+
+        duplicates
+    }
+
+    /// This method checks that the sum of the balances of all the addresses, according to the chain,
+    /// is equal to the money issued by the chain, which is the sum of the rewards of the blocks
+    /// minus the fees paid by the transactions: the fees of a block with a miner are already part of its `Coinbase`,
+    /// while the fees of a block without a miner aren't credited to anyone.
+    /// Since transactions only move money between addresses, no other money can be created or destroyed by them.
+    /// 
+    /// The money issued by every mined block is also checked against the reward schedule, so that
+    /// a block can't create money from nowhere: the genesis block is the only one allowed to premine.
+    /// 
+    /// The function returns a `Result<(), SupplyError>`, because
+    /// if the `Coinbase` of a mined block isn't equal to its reward plus its fees, a `SupplyError::InvalidCoinbase` error is returned,
+    /// if the sums don't match, a `SupplyError::Mismatch` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut sarah = Account::new("Sarah", "Smith", "K9_is_a_good_dog");
+    /// let mut harry = Account::new("Harry", "Sullivan", "harry_sullivan_1974");
+    /// let miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// sarah.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(sarah.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.set_miner(&miner.address(), 50.0).unwrap();
+    /// blockchain.push_transaction(&mut sarah, &mut harry, 4.0, "K9_is_a_good_dog").unwrap();
+    /// 
+    /// assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+    /// ```
+    pub fn verify_supply_invariant(&self) -> Result<(), SupplyError> {
+        if let Some(block) = self.chain.iter().skip(1).find(|block| !self.has_valid_coinbase(block)) {
+            return Err(SupplyError::InvalidCoinbase(block.index()));
+        }
+
+        let expected = self.issued_supply();
+
+        let actual = self.balances.values().sum::<f64>();
+
+        if (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0) {
+            Ok(())
+        } else {
+            Err(SupplyError::Mismatch { expected, actual })
+        }
+    }
+
+    /// This method returns the money issued by the chain, which is the sum of the rewards of the `Coinbase`s
+    /// of the blocks, including the genesis block, minus the fees of the blocks without a miner,
+    /// which aren't credited to anyone.
+    /// 
+    /// The supply is derived from the blocks of the chain only: the money held by the `Account`s outside
+    /// of the chain isn't counted, and collecting the rewards of a miner doesn't change it.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut martha = Account::new("Martha", "Jones", "MarthaJ_2007");
+    /// let mut mickey = Account::new("Mickey", "Smith", "M1ckey!Smith");
+    /// let miner = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// martha.add_money(30.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(martha.address(), PositiveF64::new(30.0).unwrap())]).unwrap();
+    /// blockchain.set_miner(&miner.address(), 50.0).unwrap();
+    /// blockchain.push_transaction(&mut martha, &mut mickey, 12.0, "MarthaJ_2007").unwrap();
+    /// 
+    /// assert_eq!(blockchain.total_supply().value(), 30.0 + 50.0);
+    /// ```
+    pub fn total_supply(&self) -> PositiveF64 {
+        PositiveF64::new(self.issued_supply()).unwrap_or(PositiveF64::zero())
+    }
+
+    fn issued_supply(&self) -> f64 {
+        self.chain
+            .iter()
+            .filter_map(|block| block.coinbase())
+            .map(|coinbase| coinbase.reward())
+            .sum::<f64>()
+            - self.chain
+                .iter()
+                .flat_map(|block| block.transactions())
+                .map(|transaction| transaction.fee())
+                .sum::<f64>()
+    }
+
+    /// This method returns the variance of the times between consecutive mined blocks of the chain, in seconds squared;
+    /// a high variance means that the blocks are mined at an irregular pace.
+    /// The genesis block isn't counted, since its time is fixed.
+    /// 
+    /// If the chain has less than three mined blocks, `None` is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut river = Account::new("River", "Song", "Sp0ilers!");
+    /// let mut jack = Account::new("Jack", "Harkness", "Capt41n_Jack");
+    /// river.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(river.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut river, &mut jack, 1.0, "Sp0ilers!").unwrap();
+    /// blockchain.push_transaction(&mut river, &mut jack, 1.0, "Sp0ilers!").unwrap();
+    /// 
+    /// assert_eq!(blockchain.block_time_variance(), None); // there are only two mined blocks
+    /// 
+    /// blockchain.push_transaction(&mut river, &mut jack, 1.0, "Sp0ilers!").unwrap();
+    /// 
+    /// assert!(blockchain.block_time_variance().unwrap() >= 0.0);
+    /// ```
+    pub fn block_time_variance(&self) -> Option<f64> {
+        let times: Vec<DateTime<Utc>> = self.chain.iter().skip(1).map(|block| block.time()).collect();
+
+        BlockChain::intervals_variance(&times)
+    }
+
+    /// This function returns the variance of the intervals between consecutive times, in seconds squared,
+    /// or `None` if there are less than three times.
+    fn intervals_variance(times: &[DateTime<Utc>]) -> Option<f64> {
+        if times.len() < 3 {
+            return None;
+        }
+
+        let intervals: Vec<f64> = times
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_microseconds().unwrap_or(i64::MAX) as f64 / 1_000_000.0)
+            .collect();
+
+        let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+
+        Some(intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / intervals.len() as f64)
+    }
+
+    /// This method returns the pending transactions encoded as bytes,
+    /// so that they can be restored with `.import_mempool()` after a restart;
+    /// as in the blocks, only the public parts of the accounts of the transactions are written.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut clara = Account::new("Clara", "Oswald", "clara_oswald_1866");
+    /// let mut danny = Account::new("Danny", "Pink", "D4nny_P1nk!");
+    /// clara.add_money(40.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(clara.address(), PositiveF64::new(40.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut clara, &mut danny, 15.0, "clara_oswald_1866").unwrap();
+    /// 
+    /// let mempool = blockchain.export_mempool();
+    /// 
+    /// let mut restarted = BlockChain::new(5, DIFFICULTY);
+    /// restarted.premine(vec![(clara.address(), PositiveF64::new(40.0).unwrap())]).unwrap();
+    /// 
+    /// assert_eq!(restarted.import_mempool(&mempool), Ok(1));
+    /// ```
+    pub fn export_mempool(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+
+        writer.u64(self.transactions.len() as u64);
+
+        for transaction in &self.transactions {
+            transaction.encode(&mut writer);
+        }
+
+        writer.bytes()
+    }
+
+    /// This method restores the pending transactions exported with `.export_mempool()`,
+    /// and returns the number of transactions that were accepted.
+    /// 
+    /// Every transaction is validated again, and it's discarded if it isn't valid, if its sender can't afford it
+    /// on top of its pending transactions, according to the chain, or if it's already pending or in the chain;
+    /// the balances of the accounts aren't changed, since the amounts were already transferred
+    /// when the transactions were first pushed.
+    /// 
+    /// If the bytes can't be decoded, a `MempoolError::Malformed` error is returned, and nothing is imported.
+    /// When the number of pending transactions reaches the number of `transactions_per_block`,
+    /// a new `Block` is generated, as in `.push_transaction()`; if the block can't be put in the chain,
+    /// a `MempoolError::Chain` error is returned, with the `ChainError` of the block,
+    /// and the imported transactions are still pending.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, MempoolError};
+    /// # use blockchain::block::DIFFICULTY;
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// 
+    /// assert_eq!(blockchain.import_mempool(&[1, 2, 3]), Err(MempoolError::Malformed));
+    /// ```
+    pub fn import_mempool(&mut self, data: &[u8]) -> Result<usize, MempoolError> {
+        let mut reader = Reader::new(data);
+
+        let len = reader.u64().ok_or(MempoolError::Malformed)?;
+
+        let mut transactions = Vec::new();
+
+        for _ in 0..len {
+            transactions.push(Transaction::decode(&mut reader).ok_or(MempoolError::Malformed)?);
+        }
+
+        if !reader.is_empty() {
+            return Err(MempoolError::Malformed);
+        }
+
+        let mut accepted = 0;
+
+        for transaction in transactions {
+            if self.accept_pending(transaction) {
+                accepted += 1;
+            }
+        }
+
+        if self.transactions.len() >= self.transactions_per_block {
+            self.mine_block().map_err(MempoolError::Chain)?;
+        }
+
+        Ok(accepted)
+    }
+
+    /// This method puts a transaction received from another node among the pending transactions,
+    /// if it isn't already pending or in the chain and it's valid, as in `.import_mempool()`;
+    /// it returns whether the transaction was accepted.
+    fn accept_pending(&mut self, transaction: Transaction) -> bool {
+        let is_known = self.transaction_blocks.contains_key(&transaction.hash())
+            || self.transactions.iter().any(|t| t.hash() == transaction.hash());
+
+        let is_valid = self.check_transaction(&transaction).is_ok();
+
+        if is_known || !is_valid {
+            return false;
+        }
+
+        self.index_pending(&transaction);
+        self.transactions.push(transaction);
+
+        true
+    }
+
+    /// This method returns the pending transactions, which were validated but aren't in a `Block` yet,
+    /// since the `transactions` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// ryan.add_money(20.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(ryan.address(), PositiveF64::new(20.0).unwrap())]).unwrap();
+    /// 
+    /// assert!(blockchain.pending_transactions().is_empty());
+    /// 
+    /// blockchain.push_transaction(&mut ryan, &mut yaz, 5.0, "ryan_sinclair_2018").unwrap();
+    /// 
+    /// assert_eq!(blockchain.pending_transactions()[0].amount(), 5.0);
+    /// ```
+    pub fn pending_transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// This method returns the pending transactions, not yet in a `Block`, sent or received by the address.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut bill = Account::new("Bill", "Potts", "Bill_Potts_2017");
+    /// let mut nardole = Account::new("Nardole", "Nardole", "n4rd0l3!");
+    /// bill.add_money(20.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(bill.address(), PositiveF64::new(20.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut bill, &mut nardole, 5.0, "Bill_Potts_2017").unwrap();
+    /// 
+    /// assert_eq!(blockchain.pending_for(&bill.address()).len(), 1);
+    /// assert_eq!(blockchain.pending_for(&nardole.address()).len(), 1); // incoming transactions are included
+    /// ```
+    pub fn pending_for(&self, address: &str) -> Vec<&Transaction> {
+        self.transactions
+            .iter()
+            .filter(|t| t.sender.address() == address || t.receiver.address() == address)
+            .collect()
+    }
+
+    /// This method returns every transaction sent or received by the address, in the order of the blocks
+    /// and, within a block, in the order of its transactions; the pending transactions come last.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut graham = Account::new("Graham", "O'Brien", "graham_bus_driver");
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// graham.add_money(20.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(2, DIFFICULTY);
+    /// blockchain.premine(vec![(graham.address(), PositiveF64::new(20.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut graham, &mut yaz, 5.0, "graham_bus_driver").unwrap();
+    /// blockchain.push_transaction(&mut graham, &mut yaz, 3.0, "graham_bus_driver").unwrap(); // the block is sealed
+    /// blockchain.push_transaction(&mut yaz, &mut graham, 1.0, "yaz_khan_2018").unwrap(); // still pending
+    /// 
+    /// let amounts: Vec<f64> = blockchain.transaction_history(&yaz.address()).iter().map(|t| t.amount()).collect();
+    /// 
+    /// assert_eq!(amounts, [5.0, 3.0, 1.0]);
+    /// ```
+    pub fn transaction_history(&self, address: &str) -> Vec<&Transaction> {
+        self.chain
+            .iter()
+            .flat_map(|block| block.transactions())
+            .chain(&self.transactions)
+            .filter(|t| t.sender.address() == address || t.receiver.address() == address)
+            .collect()
+    }
+
+    /// This method returns the SHA-512 hash of the sorted hashes of the pending transactions,
+    /// so that two nodes can check that their pending transactions are the same by comparing only the digests;
+    /// the digest doesn't depend on the order the transactions were pushed in.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut wilf = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// let mut sylvia = Account::new("Sylvia", "Noble", "Sylvia_Noble_1950");
+    /// wilf.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(5, DIFFICULTY);
+    /// blockchain.premine(vec![(wilf.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// let empty_digest = blockchain.mempool_digest();
+    /// 
+    /// blockchain.push_transaction(&mut wilf, &mut sylvia, 5.0, "wilf_mott_1930").unwrap();
+    /// 
+    /// assert_ne!(blockchain.mempool_digest(), empty_digest);
+    /// ```
+    pub fn mempool_digest(&self) -> [u8; 64] {
+        let mut hashes: Vec<[u8; 64]> = self.transactions.iter().map(|t| t.hash()).collect();
+
+        hashes.sort_unstable();
+
+        let mut hasher = Sha512::new();
+
+        hasher.update(hashes.concat());
+
+        hasher
+            .finalize()[..]
+            .try_into()
+            .expect("Error generating the SHA-512 hash of the pending transactions.")
+    }
+
+    /// This method returns the metrics of the chain in the Prometheus text exposition format,
+    /// so that a node can be monitored by scraping them.
+    /// 
+    /// The following gauges are exported:
+    /// - `blockchain_height`, the index of the last block of the chain
+    /// - `blockchain_mempool_size`, the number of pending transactions
+    /// - `blockchain_total_supply`, the money issued by the chain, as returned by `total_supply`
+    /// - `blockchain_account_balance`, the balance of each of the addresses with the highest balances,
+    ///   labelled with the address
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// 
+    /// assert!(blockchain.prometheus_metrics().contains("\nblockchain_height 0\n"));
+    /// ```
+    pub fn prometheus_metrics(&self) -> String {
+        let mut balances: Vec<(&String, &f64)> = self.balances.iter().collect();
+
+        balances.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut metrics = String::new();
+
+        let mut gauge = |name: &str, help: &str, samples: Vec<(String, f64)>| {
+            metrics.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n", name, help, name));
+
+            for (labels, value) in samples {
+                metrics.push_str(&format!("{}{} {}\n", name, labels, value));
+            }
+        };
+
+        gauge("blockchain_height", "The index of the last block of the chain.", vec![(String::new(), self.index as f64)]);
+        gauge("blockchain_mempool_size", "The number of pending transactions.", vec![(String::new(), self.transactions.len() as f64)]);
+        gauge("blockchain_total_supply", "The money issued by the chain.", vec![(String::new(), self.total_supply().value())]);
+        gauge(
+            "blockchain_account_balance",
+            "The balance of the addresses with the highest balances.",
+            balances
+                .into_iter()
+                .take(METRICS_TOP_ACCOUNTS)
+                .map(|(address, balance)| (format!("{{address=\"{}\"}}", address), *balance))
+                .collect(),
+        );
+
+        metrics
+    }
+}
+
+impl<'a> IntoIterator for &'a BlockChain {
+    type Item = &'a Block;
+    type IntoIter = std::slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An enum to handle errors generated while validating the `BlockChain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    BrokenGenesisLink,
+    BrokenLink(usize),
+    NonMonotonicTime(usize),
+    BelowMedianTime(usize),
+    InvalidRollback(usize),
+    InvalidBlock(usize, BlockError),
+    InvalidTransaction(usize, ValidationError),
+    InvalidReversal(usize),
+    InvalidCoinbase(usize),
+    LatePremine,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::BrokenGenesisLink => write!(f, "Broken link to the genesis block."),
+            Self::BrokenLink(index) => write!(f, "Broken link at block #{}.", index),
+            Self::NonMonotonicTime(index) => write!(f, "Block #{} is older than the block before it.", index),
+            Self::BelowMedianTime(index) => write!(f, "Block #{} isn't later than the median time of the blocks before it.", index),
+            Self::InvalidRollback(n) => write!(f, "Can't remove {} blocks without removing the genesis block.", n),
+            Self::InvalidBlock(index, e) => write!(f, "Invalid block #{}: {}", index, e),
+            Self::InvalidTransaction(index, e) => write!(f, "Invalid transaction in block #{}: {}", index, e),
+            Self::InvalidReversal(index) => write!(f, "Block #{} reverses a transaction which can't be reversed.", index),
+            Self::InvalidCoinbase(index) => write!(f, "The coinbase of block #{} doesn't match with its reward and its fees.", index),
+            Self::LatePremine => write!(f, "Can't premine once a block is put on top of the genesis block."),
+        }
+    }
+}
+
+impl error::Error for ChainError {}
+
+/// An enum to handle errors generated while pushing a transaction into the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushError {
+    Invalid(ValidationError),
+    Chain(ChainError),
+}
+
+impl From<ValidationError> for PushError {
+    fn from(e: ValidationError) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Invalid(e) => write!(f, "{}", e),
+            Self::Chain(e) => write!(f, "The transaction is pending, but its block was rejected: {}", e),
+        }
+    }
+}
+
+impl error::Error for PushError {}
+
+/// An enum to handle errors generated while checking the money supply of the `BlockChain`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SupplyError {
+    InvalidCoinbase(usize),
+    Mismatch {
+        expected: f64,
+        actual: f64,
+    },
+}
+
+impl fmt::Display for SupplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidCoinbase(index) => write!(f, "The coinbase of block #{} issues money outside of the reward schedule.", index),
+            Self::Mismatch { expected, actual } => write!(f, "Money supply mismatch: expected {}, found {}.", expected, actual),
+        }
+    }
+}
+
+impl error::Error for SupplyError {}
+
+/// An enum to handle errors generated while importing the pending transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MempoolError {
+    Malformed,
+    Chain(ChainError),
+}
+
+impl fmt::Display for MempoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Malformed => write!(f, "Malformed mempool data."),
+            Self::Chain(e) => write!(f, "The transactions are pending, but their block was rejected: {}", e),
+        }
+    }
+}
+
+impl error::Error for MempoolError {}
+
+/// An enum to handle errors generated while splitting the reward of a `Block` between miners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PayoutError {
+    InvalidShare,
+    Mismatch {
+        expected: f64,
+        actual: f64,
+    },
+    Chain(ChainError),
+}
+
+impl fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidShare => write!(f, "Invalid payout share."),
+            Self::Mismatch { expected, actual } => write!(f, "Payouts mismatch: expected {}, found {}.", expected, actual),
+            Self::Chain(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for PayoutError {}
+
+/// An enum to handle errors generated while setting the reward schedule of the miners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardError {
+    InvalidReward(InvalidNumber),
+    FrozenSchedule,
+}
+
+impl fmt::Display for RewardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidReward(e) => write!(f, "Invalid reward: {}", e),
+            Self::FrozenSchedule => write!(f, "The reward schedule can't be changed after the first block."),
+        }
+    }
+}
+
+impl error::Error for RewardError {}
+
+/// An enum to handle errors generated while reversing a `Transaction` of the `BlockChain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargebackError {
+    Unauthorized,
+    UnknownTransaction,
+    AlreadyReversed,
+    InsufficientFunds,
+    Chain(ChainError),
+}
+
+impl fmt::Display for ChargebackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Unauthorized => write!(f, "Chargeback not authorized by the admin."),
+            Self::UnknownTransaction => write!(f, "Unknown transaction."),
+            Self::AlreadyReversed => write!(f, "Transaction already reversed."),
+            Self::InsufficientFunds => write!(f, "The receiver can't afford the reversal."),
+            Self::Chain(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for ChargebackError {}
+
+/// The representation of a `BlockChain` used by `serde`: the indexes of the chain, the difficulty of the next block
+/// and the validated height aren't written, since they're computed again from the blocks,
+/// and the custom rules can't be written at all.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerdeBlockChain {
+    chain: Vec<Block>,
+    transactions: Vec<Transaction>,
+    transactions_per_block: usize,
+    difficulty: usize,
+    target_block_time: Option<Duration>,
+    adjustment_interval: usize,
+    miner: Option<String>,
+    initial_reward: PositiveF64,
+    halving_interval: usize,
+    coinbase_maturity: usize,
+    collected_rewards: BTreeMap<String, PositiveF64>,
+    admin: Option<String>,
+    network_id: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl BlockChain {
+    /// This method saves the blockchain to a JSON file, which can be loaded back with `BlockChain::load_from_file()`.
+    /// 
+    /// The custom rules added with `.add_validator()` aren't saved, so they must be added again after loading the chain.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut romana = Account::new("Romana", "Dvoratrelundar", "romanadvoratrelundar");
+    /// let mut adric = Account::new("Adric", "Alzarius", "adric_maths_badge");
+    /// romana.add_money(10.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(romana.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut romana, &mut adric, 4.0, "romanadvoratrelundar").unwrap();
+    /// 
+    /// let path = std::env::temp_dir().join("e_space.json");
+    /// 
+    /// blockchain.save_to_file(&path).unwrap();
+    /// 
+    /// assert_eq!(BlockChain::load_from_file(&path), Ok(blockchain));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let saved = SerdeBlockChain {
+            chain: self.chain.clone(),
+            transactions: self.transactions.clone(),
+            transactions_per_block: self.transactions_per_block,
+            difficulty: self.difficulty,
+            target_block_time: self.target_block_time,
+            adjustment_interval: self.adjustment_interval,
+            miner: self.miner.clone(),
+            initial_reward: self.initial_reward,
+            halving_interval: self.halving_interval,
+            coinbase_maturity: self.coinbase_maturity,
+            collected_rewards: self.collected_rewards.clone().into_iter().collect(),
+            admin: self.admin.map(|admin| to_hex(&admin)),
+            network_id: self.network_id.clone(),
+        };
+
+        fs::write(path, serde_json::to_string_pretty(&saved)?)
+    }
+
+    /// Loads a `BlockChain` from a JSON file saved by `.save_to_file()`; the whole chain is checked
+    /// with `.validate_chain()`, and the indexes of the chain, the difficulty of the next block
+    /// and the validated height are computed again from its blocks.
+    /// The pending transactions are checked again as in `.import_mempool()`, and the invalid ones are discarded.
+    /// 
+    /// The function returns a `Result<BlockChain, LoadError>`, because
+    /// if the file can't be read, a `LoadError::Io` error is returned,
+    /// if the file isn't a valid saved blockchain, or its difficulty is greater than `MAX_DIFFICULTY`,
+    /// a `LoadError::Parse` error is returned,
+    /// and if the chain was tampered with, a `LoadError::Invalid` error is returned, with the `ChainError` of the chain.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::{BlockChain, LoadError};
+    /// let path = std::env::temp_dir().join("not_a_blockchain.json");
+    /// 
+    /// std::fs::write(&path, "{}").unwrap();
+    /// 
+    /// assert!(matches!(BlockChain::load_from_file(&path), Err(LoadError::Parse(_))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn load_from_file(path: &Path) -> Result<Self, LoadError> {
+        let contents = fs::read_to_string(path).map_err(|e| LoadError::Io(e.kind()))?;
+        let saved: SerdeBlockChain = serde_json::from_str(&contents).map_err(|e| LoadError::Parse(e.to_string()))?;
+
+        if saved.chain.is_empty() {
+            return Err(LoadError::Parse(String::from("missing genesis block")));
+        }
+
+        if saved.difficulty > MAX_DIFFICULTY {
+            return Err(LoadError::Parse(format!("the difficulty can't be greater than {}", MAX_DIFFICULTY)));
+        }
+
+        fn array<const N: usize>(hex: &str) -> Result<[u8; N], LoadError> {
+            from_hex(hex)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| LoadError::Parse(String::from("invalid hex array")))
+        }
+
+        let mut blockchain = Self {
+            index: saved.chain.len() - 1,
+            chain: saved.chain,
+            transactions: Vec::new(),
+            transactions_per_block: saved.transactions_per_block,
+            difficulty: saved.difficulty,
+            current_difficulty: saved.difficulty,
+            target_block_time: saved.target_block_time,
+            adjustment_interval: saved.adjustment_interval,
+            known_addresses: HashSet::new(),
+            balances: HashMap::new(),
+            pending_balances: HashMap::new(),
+            immature_rewards: HashMap::new(),
+            next_nonces: HashMap::new(),
+            pending_nonces: HashMap::new(),
+            transaction_blocks: HashMap::new(),
+            miner: saved.miner,
+            initial_reward: saved.initial_reward,
+            halving_interval: saved.halving_interval,
+            coinbase_maturity: saved.coinbase_maturity,
+            collected_rewards: saved.collected_rewards.into_iter().collect(),
+            validated_height: 0,
+            admin: saved.admin.map(|admin| array(&admin)).transpose()?,
+            charged_back: HashSet::new(),
+            network_id: saved.network_id,
+            rules: ValidationRules::default(),
+            observer: Observer::default(),
+        };
+
+        blockchain.validate_chain().map_err(LoadError::Invalid)?;
+
+        // the whole chain was just validated
+        blockchain.validated_height = blockchain.index;
+
+        blockchain.rebuild_indexes();
+        blockchain.rebuild_difficulty();
+
+        for transaction in saved.transactions {
+            blockchain.accept_pending(transaction);
+        }
+
+        Ok(blockchain)
+    }
+}
+
+/// An enum to handle errors generated while loading a `BlockChain` from a file.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    Io(io::ErrorKind),
+    Parse(String),
+    Invalid(ChainError),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "Couldn't read the blockchain file ({:?}).", kind),
+            Self::Parse(message) => write!(f, "Invalid blockchain file: {}.", message),
+            Self::Invalid(e) => write!(f, "Invalid blockchain: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl error::Error for LoadError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::DIFFICULTY, transaction::{TransactionBuilder, Condition}};
+
+    /// This function credits the accounts in the genesis block of the chain with the amounts
+    /// added to them outside of the chain, so that the chain lets them spend the money.
+    fn fund_in_genesis(blockchain: &mut BlockChain, accounts: &[(&Account, f64)]) {
+        let allocations = accounts
+            .iter()
+            .map(|(account, amount)| (account.address(), PositiveF64::new(*amount).unwrap()))
+            .collect();
+
+        blockchain.premine(allocations).unwrap();
+    }
+
+    /// This function generates a chain with the given number of transactions per block, and two accounts:
+    /// the sender, which has 10 of money both in its `Account` and in the genesis block, and the receiver.
+    fn funded_chain(transactions_per_block: usize) -> (BlockChain, Account, Account) {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(transactions_per_block, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+
+        (blockchain, sender, receiver)
+    }
+
+    #[test]
+    fn tampered_genesis_breaks_validation() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.is_valid(), Ok(()));
+
+        blockchain.chain[0] = Block::new(0, [1; 64], Vec::new(), DIFFICULTY);
+
+        assert_eq!(blockchain.is_valid(), Err(ChainError::BrokenGenesisLink));
+    }
+
+    #[test]
+    fn tampered_transaction_is_found_by_validate_chain() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        let block = blockchain.chain[1].clone();
+        let mut transactions = block.transactions().to_vec();
+        transactions[0].tamper_amount(500.0);
+
+        // the block commits to the hashes of its transactions, so it's mined again with the same hash
+        blockchain.chain[1] = Block::build(block.index(), block.prev_hash(), transactions, block.coinbase().cloned(), block.time(), DIFFICULTY);
+
+        assert_eq!(blockchain.chain[1].hash(), block.hash());
+        assert_eq!(blockchain.is_valid(), Ok(()));
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidTransaction(1, ValidationError::Tempered)));
+    }
+
+    #[test]
+    fn rejected_transaction_leaves_the_accounts_untouched() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "wrong_password"), Err(PushError::Invalid(ValidationError::WrongPassword)));
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 50.0, "sender_password"), Err(PushError::Invalid(ValidationError::InvalidAmount)));
+        assert_eq!(sender.balance(), 10.0);
+        assert_eq!(receiver.balance(), 0.0);
+        assert_eq!(blockchain.chain.len(), 1);
+        assert!(blockchain.transactions.is_empty());
+    }
+
+    #[test]
+    fn block_timestamps_never_decrease() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+
+        for _ in 0..3 {
+            blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        }
+
+        let mut fresh = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut fresh, &[(&sender, 10.0)]);
+
+        assert_eq!(blockchain.chain[0], fresh.chain[0]);
+        assert_eq!(blockchain.chain[0].timestamp(), 0);
+        assert!(blockchain.chain.windows(2).all(|pair| pair[1].timestamp() >= pair[0].timestamp()));
+    }
+
+    #[test]
+    fn last_activity_is_the_time_of_the_latest_block() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        let mut other = Account::new("Other", "Account", "other_password");
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut other, 1.0, "sender_password").unwrap();
+
+        let time_of = |index: usize| Some(blockchain.chain[index].time().timestamp() as u64);
+
+        assert_eq!(blockchain.last_activity(&receiver.address()), time_of(1));
+        assert_eq!(blockchain.last_activity(&sender.address()), time_of(2));
+        assert_eq!(blockchain.last_activity(&other.address()), time_of(2));
+    }
+
+    #[test]
+    fn confirmed_balance_excludes_recent_blocks() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.confirmed_balance_of(&receiver.address(), 2), 0.0);
+        assert_eq!(blockchain.confirmed_balance_of(&sender.address(), 2), 10.0); // only the genesis block is confirmed twice
+
+        let block = Block::new(2, blockchain.chain[1].hash(), Vec::new(), DIFFICULTY);
+        blockchain.append_block(block).unwrap();
+
+        assert_eq!(blockchain.confirmed_balance_of(&receiver.address(), 2), 5.0);
+        assert_eq!(blockchain.confirmed_balance_of(&sender.address(), 2), 5.0);
+        assert_eq!(blockchain.confirmed_balance_of(&receiver.address(), 3), 0.0);
+    }
+
+    #[test]
+    fn custom_rules_are_enforced_with_built_in_checks() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let mut blockchain = BlockChain::new(5, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 100.0)]);
+        blockchain.add_validator(Box::new(|transaction, _| {
+            if transaction.amount() <= 10.0 {
+                Ok(())
+            } else {
+                Err(ValidationError::InvalidAmount)
+            }
+        }));
+
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 20.0, "sender_password"), Err(PushError::Invalid(ValidationError::InvalidAmount))); // above the threshold
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "wrong_password"), Err(PushError::Invalid(ValidationError::WrongPassword)));
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.transactions.len(), 1);
+        assert_eq!(blockchain.transactions[0].amount(), 5.0);
+        assert_eq!(sender.balance(), 95.0);
+        assert_eq!(blockchain.clone(), blockchain);
+    }
+
+    #[test]
+    fn rebuild_indexes_after_manual_blocks() {
+        let (mut indexed, mut sender, mut receiver) = funded_chain(1);
+        indexed.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+        indexed.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.chain = indexed.chain.clone();
+        blockchain.index = indexed.index;
+
+        let hash = indexed.chain[2].transactions()[0].hash();
+
+        assert!(blockchain.find_transaction(&hash).is_none());
+        assert_ne!(blockchain.state_merkle_root(), indexed.state_merkle_root());
+
+        blockchain.rebuild_indexes();
+
+        assert_eq!(blockchain.find_transaction(&hash).map(|t| t.amount()), Some(1.0));
+        assert_eq!(blockchain.transaction_blocks.get(&hash), Some(&2));
+        assert_eq!(blockchain.balances, indexed.balances);
+        assert_eq!(blockchain.balances[&receiver.address()], 5.0);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 5.0);
+        assert_eq!(blockchain.known_addresses, indexed.known_addresses);
+    }
+
+    #[test]
+    fn blocks_are_found_by_index_and_hash() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        let mined = &blockchain.chain[1];
+
+        assert_eq!(blockchain.get_block(0), Some(&blockchain.chain[0]));
+        assert_eq!(blockchain.get_block(2), None);
+        assert_eq!(blockchain.block_by_hash(&mined.hash()), Some(mined));
+        assert_eq!(blockchain.block_by_hash(&mined.prev_hash()), blockchain.get_block(0));
+        assert_eq!(blockchain.block_by_hash(&[0; 64]), None);
+    }
+
+    #[test]
+    fn transaction_history_is_in_chain_order() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        let mut other = Account::new("Other", "Account", "other_password");
+        sender.add_money(20.0).unwrap();
+        other.add_money(20.0).unwrap();
+
+        let mut blockchain = BlockChain::new(2, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 20.0), (&other, 20.0)]);
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut other, &mut sender, 2.0, "other_password").unwrap();
+        blockchain.push_transaction(&mut other, &mut receiver, 3.0, "other_password").unwrap();
+        blockchain.push_transaction(&mut receiver, &mut sender, 4.0, "receiver_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut other, 5.0, "sender_password").unwrap(); // still pending
+
+        let history: Vec<f64> = blockchain
+            .transaction_history(&sender.address())
+            .iter()
+            .map(|t| t.amount())
+            .collect();
+
+        assert_eq!(blockchain.chain.len(), 3);
+        assert_eq!(history, [1.0, 2.0, 4.0, 5.0]);
+        assert!(blockchain.transaction_history("unknown_address").is_empty());
+    }
+
+    #[test]
+    fn balance_of_follows_mined_transfers() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        let mut middle = Account::new("Middle", "Account", "middle_password");
+        let miner = Account::new("Miner", "Account", "miner_password");
+
+        blockchain.set_miner(&miner.address(), 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut middle, 10.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut middle, &mut receiver, 4.0, "middle_password").unwrap();
+
+        assert_eq!(blockchain.chain.len(), 3);
+        assert_eq!(blockchain.balance_of(&middle.address()).value(), 6.0);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 4.0);
+        assert_eq!(blockchain.balance_of(&miner.address()).value(), 100.0);
+        assert_eq!(blockchain.balance_of(&sender.address()).value(), 0.0);
+        assert_eq!(blockchain.balance_of(&middle.address()).value(), middle.balance());
+    }
+
+    #[test]
+    fn pending_transactions_prevent_double_spending() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let mut stale_sender = sender.clone();
+        let mut blockchain = BlockChain::new(5, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 100.0)]);
+
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 60.0, "sender_password"), Ok(()));
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 60.0, "sender_password"), Err(PushError::Invalid(ValidationError::InvalidAmount)));
+        assert_eq!(blockchain.push_transaction(&mut stale_sender, &mut receiver, 60.0, "sender_password"), Err(PushError::Invalid(ValidationError::InvalidAmount)));
+        assert_eq!(stale_sender.balance(), 100.0);
+        assert_eq!(blockchain.transactions.len(), 1);
+
+        assert_eq!(blockchain.push_transaction(&mut stale_sender, &mut receiver, 40.0, "sender_password"), Err(PushError::Invalid(ValidationError::StaleNonce)));
+        assert_eq!(blockchain.transactions.len(), 1);
+    }
+
+    #[test]
+    fn replayed_transactions_have_stale_nonces() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let mut stale_sender = sender.clone();
+        let mut blockchain = BlockChain::new(5, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 100.0)]);
+        blockchain.push_transaction(&mut sender, &mut receiver, 10.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 10.0, "sender_password").unwrap();
+
+        assert_ne!(blockchain.transactions[0].hash(), blockchain.transactions[1].hash());
+        assert_eq!(sender.nonce(), 2);
+
+        let mempool = blockchain.export_mempool();
+
+        blockchain.flush().unwrap();
+
+        assert_eq!(blockchain.import_mempool(&mempool), Ok(0));
+        assert_eq!(
+            blockchain.push_transaction(&mut stale_sender, &mut receiver, 10.0, "sender_password"),
+            Err(PushError::Invalid(ValidationError::StaleNonce)),
+        );
+        assert_eq!(blockchain.push_transaction(&mut sender, &mut receiver, 10.0, "sender_password"), Ok(()));
+    }
+
+    #[test]
+    fn reversed_transactions_cant_be_replayed() {
+        let admin = Account::new("Admin", "Account", "admin_password");
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(5);
+        blockchain.set_admin(admin.public_key());
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let mempool = blockchain.export_mempool();
+        let hash = blockchain.transactions[0].hash();
+
+        blockchain.flush().unwrap();
+
+        assert_eq!(blockchain.chargeback(&hash, &admin.keypair()[..32].try_into().unwrap()), Ok(()));
+        assert_eq!(blockchain.next_nonce(&sender.address()), 1);
+        assert_eq!(blockchain.import_mempool(&mempool), Ok(0));
+        assert!(blockchain.flush().is_none());
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn iterator_yields_every_block_from_the_genesis() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 3.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.iter().count(), 3);
+        assert_eq!(blockchain.iter().next().map(Block::index), Some(0));
+
+        let mut transactions = 0;
+
+        for block in &blockchain {
+            transactions += block.transactions().len();
+        }
+
+        assert_eq!(transactions, 2);
+    }
+
+    #[test]
+    fn replace_chain_adopts_only_longer_valid_chains() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+
+        let mut peer = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut peer, &[(&sender, 10.0)]);
+        peer.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+        peer.push_transaction(&mut sender, &mut receiver, 3.0, "sender_password").unwrap();
+
+        let mut invalid = peer.chain();
+        invalid[2] = Block::new(2, [0; 64], Vec::new(), DIFFICULTY);
+
+        assert_eq!(blockchain.replace_chain(invalid), Err(ChainError::InvalidBlock(2, BlockError::InvalidPrevHash)));
+        assert_eq!(blockchain.chain.len(), 1);
+
+        assert_eq!(blockchain.replace_chain(peer.chain()), Ok(true));
+        assert_eq!(blockchain.chain, peer.chain);
+        assert_eq!(blockchain.index, 2);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 5.0);
+
+        let shorter = peer.chain()[..2].to_vec();
+
+        assert_eq!(blockchain.replace_chain(shorter), Ok(false));
+        assert_eq!(blockchain.chain.len(), 3);
+        assert_eq!(blockchain.replace_chain(BlockChain::new(1, 1).chain()), Err(ChainError::BrokenGenesisLink));
+    }
+
+    #[test]
+    fn replace_chain_recomputes_the_difficulty() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, 0);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+        blockchain.set_target_block_time(Duration::from_secs(60), 2);
+        let mut peer = blockchain.clone();
+
+        for _ in 0..4 {
+            peer.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        }
+
+        assert_eq!(peer.current_difficulty(), 1);
+        assert_eq!(blockchain.replace_chain(peer.chain()), Ok(true));
+        assert_eq!(blockchain.current_difficulty(), 1);
+    }
+
+    #[test]
+    fn reward_schedule_changes_are_refused_after_the_first_block() {
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+        blockchain.mine_block().unwrap();
+
+        assert_eq!(blockchain.set_miner("miner_address", 25.0), Err(RewardError::FrozenSchedule));
+        assert_eq!(blockchain.set_halving_interval(1), Err(RewardError::FrozenSchedule));
+
+        blockchain.mine_block().unwrap();
+
+        assert_eq!(blockchain.block_reward(2).value(), 50.0);
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn flush_seals_an_undersized_block() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(3);
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.chain.len(), 1);
+        assert_eq!(blockchain.flush().map(|block| block.index()), Some(1));
+        assert_eq!(blockchain.chain.len(), 2);
+        assert!(blockchain.transactions.is_empty());
+        assert!(blockchain.flush().is_none());
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn miner_collects_reward_and_fees() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(2);
+        let miner = Account::new("Miner", "Account", "miner_password");
+
+        blockchain.set_miner(&miner.address(), 50.0).unwrap();
+
+        for (amount, fee) in [(3.0, 0.25), (2.0, 0.5)] {
+            let transaction = TransactionBuilder::new(sender.clone(), receiver.clone(), amount, "sender_password")
+                .fee(fee)
+                .build()
+                .unwrap();
+
+            blockchain.submit_transaction(&mut sender, &mut receiver, transaction).unwrap();
+        }
+
+        assert_eq!(blockchain.chain.len(), 2);
+        assert_eq!(blockchain.chain[1].coinbase().unwrap().reward(), 50.75);
+        assert_eq!(blockchain.balance_of(&miner.address()).value(), 50.0 + 0.25 + 0.5);
+        assert_eq!(blockchain.balance_of(&sender.address()).value(), 10.0 - 3.25 - 2.5);
+        assert_eq!(sender.balance(), 10.0 - 3.25 - 2.5);
+        assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn height_locked_transactions_wait_for_their_height() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+
+        let locked = TransactionBuilder::new(sender.clone(), receiver.clone(), 3.0, "sender_password")
+            .condition(Condition::HeightAtLeast(2))
+            .build()
+            .unwrap();
+
+        let mut mempool = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut mempool, &[(&sender, 10.0)]);
+        mempool.transactions.push(locked.clone());
+
+        // the next block would be #1
+        assert_eq!(
+            blockchain.submit_transaction(&mut sender, &mut receiver, locked.clone()),
+            Err(PushError::Invalid(ValidationError::ConditionNotMet)),
+        );
+        assert_eq!(blockchain.import_mempool(&mempool.export_mempool()), Ok(0));
+        assert_eq!(sender.balance(), 10.0);
+
+        blockchain.mine_block().unwrap();
+        blockchain.submit_transaction(&mut sender, &mut receiver, locked.clone()).unwrap();
+
+        assert_eq!(blockchain.chain[2].transactions()[0].hash(), locked.hash());
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        // a block can't spend the transaction before its height either
+        blockchain.rollback(2).unwrap();
+        blockchain.append_block(Block::new(1, blockchain.chain[0].hash(), vec![locked], DIFFICULTY)).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidTransaction(1, ValidationError::ConditionNotMet)));
+    }
+
+    #[test]
+    fn pending_transactions_wait_for_their_height_after_a_rollback() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(5);
+        blockchain.mine_block().unwrap();
+
+        let locked = TransactionBuilder::new(sender.clone(), receiver.clone(), 3.0, "sender_password")
+            .condition(Condition::HeightAtLeast(2))
+            .build()
+            .unwrap();
+
+        blockchain.submit_transaction(&mut sender, &mut receiver, locked).unwrap();
+        blockchain.rollback(1).unwrap();
+
+        assert!(blockchain.mine_block().unwrap().transactions().is_empty());
+        assert_eq!(blockchain.pending_transactions().len(), 1);
+        assert_eq!(blockchain.mine_block().unwrap().transactions().len(), 1);
+        assert!(blockchain.pending_transactions().is_empty());
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn hash_locked_transactions_are_refused() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+
+        let locked = TransactionBuilder::new(sender.clone(), receiver.clone(), 3.0, "sender_password")
+            .condition(Condition::HashLock([0; 64]))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            blockchain.submit_transaction(&mut sender, &mut receiver, locked),
+            Err(PushError::Invalid(ValidationError::ConditionNotMet)),
+        );
+    }
+
+    #[test]
+    fn submitted_transactions_must_match_the_accounts() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(2);
+        let mut other = Account::new("Other", "Account", "other_password");
+
+        let transaction = Transaction::new(sender.clone(), receiver.clone(), 3.0, "sender_password").unwrap();
+
+        assert_eq!(
+            blockchain.submit_transaction(&mut sender, &mut other, transaction.clone()),
+            Err(PushError::Invalid(ValidationError::InvalidSignature)),
+        );
+        assert_eq!(sender.balance(), 10.0);
+
+        blockchain.submit_transaction(&mut sender, &mut receiver, transaction.clone()).unwrap();
+
+        // the nonce of the sender was used by the first submission
+        assert_eq!(
+            blockchain.submit_transaction(&mut sender, &mut receiver, transaction),
+            Err(PushError::Invalid(ValidationError::StaleNonce)),
+        );
+        assert_eq!(sender.balance(), 7.0);
+    }
+
+    #[test]
+    fn validated_height_follows_appends_and_rollbacks() {
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+
+        let first = Block::new(1, blockchain.chain[0].hash(), Vec::new(), DIFFICULTY);
+        let second = Block::new(2, first.hash(), Vec::new(), DIFFICULTY);
+        let unlinked = Block::new(2, [1; 64], Vec::new(), DIFFICULTY);
+
+        blockchain.append_block(first).unwrap();
+
+        assert_eq!(blockchain.validated_height(), 1);
+        assert_eq!(blockchain.append_block(unlinked), Err(ChainError::BrokenLink(2)));
+        assert_eq!(blockchain.validated_height(), 1);
+
+        blockchain.append_block(second).unwrap();
+
+        assert_eq!(blockchain.validated_height(), 2);
+
+        blockchain.rollback(1).unwrap();
+
+        assert_eq!(blockchain.validated_height(), 1);
+        assert_eq!(blockchain.index, 1);
+        assert_eq!(blockchain.is_valid(), Ok(()));
+    }
+
+    #[test]
+    fn backdated_block_breaks_validation() {
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+
+        let genesis = &blockchain.chain[0];
+        let backdated = Block::build(1, genesis.hash(), Vec::new(), None, genesis.time() - chrono::Duration::seconds(10), DIFFICULTY);
+
+        blockchain.chain.push(backdated);
+        blockchain.index += 1;
+
+        assert_eq!(blockchain.is_valid(), Err(ChainError::NonMonotonicTime(1)));
+    }
+
+    #[test]
+    fn rejected_block_is_reported_by_push_transaction() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+
+        let genesis = &blockchain.chain[0];
+        let future = Block::build(1, genesis.hash(), Vec::new(), None, Utc::now() + chrono::Duration::hours(1), DIFFICULTY);
+
+        blockchain.chain.push(future);
+        blockchain.index += 1;
+
+        assert_eq!(
+            blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password"),
+            Err(PushError::Chain(ChainError::NonMonotonicTime(2))),
+        );
+        assert_eq!(blockchain.pending_transactions().len(), 1); // the transaction was accepted
+        assert_eq!(blockchain.chain().len(), 2);
+    }
+
+    #[test]
+    fn block_below_median_time_is_rejected() {
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+
+        let start = blockchain.chain[0].time();
+
+        for (index, seconds) in [10, 20, 5].iter().enumerate() {
+            let prev_hash = blockchain.chain[index].hash();
+
+            blockchain.chain.push(Block::build(index + 1, prev_hash, Vec::new(), None, start + chrono::Duration::seconds(*seconds), DIFFICULTY));
+            blockchain.index += 1;
+        }
+
+        let expected = |seconds| Some((start + chrono::Duration::seconds(seconds)).timestamp() as u64);
+
+        assert_eq!(blockchain.median_time_past(3), expected(10));
+        assert_eq!(blockchain.median_time_past(4), expected(10));
+        assert_eq!(blockchain.median_time_past(1), expected(5));
+
+        let late = Block::build(4, blockchain.chain[3].hash(), Vec::new(), None, start + chrono::Duration::seconds(6), DIFFICULTY);
+
+        assert_eq!(blockchain.append_block(late), Err(ChainError::BelowMedianTime(4)));
+
+        let later = Block::build(4, blockchain.chain[3].hash(), Vec::new(), None, start + chrono::Duration::seconds(11), DIFFICULTY);
+
+        assert_eq!(blockchain.append_block(later), Ok(()));
+    }
+
+    #[test]
+    fn prometheus_metrics_expose_the_chain() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let metrics = blockchain.prometheus_metrics();
+
+        let height = metrics
+            .lines()
+            .find_map(|line| line.strip_prefix("blockchain_height "))
+            .and_then(|value| value.parse::<f64>().ok());
+
+        assert_eq!(height, Some(1.0));
+
+        for name in &["blockchain_mempool_size 0", "blockchain_total_supply 60", "# TYPE blockchain_account_balance gauge"] {
+            assert!(metrics.contains(name), "missing {}", name);
+        }
+
+        assert!(metrics.contains(&format!("blockchain_account_balance{{address=\"{}\"}} 4", receiver.address())));
+        assert!(metrics.contains("blockchain_account_balance{address=\"miner_address\"} 50"));
+    }
+
+    #[test]
+    fn mempool_round_trip() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(3);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        let mempool = blockchain.export_mempool();
+
+        assert!(!mempool.windows(32).any(|window| window == &sender.keypair()[..32]));
+
+        let mut unfunded = BlockChain::new(3, DIFFICULTY);
+
+        assert_eq!(unfunded.import_mempool(&mempool), Ok(0)); // the sender has no money in this chain
+
+        let mut restarted = BlockChain::new(3, DIFFICULTY);
+        fund_in_genesis(&mut restarted, &[(&sender, 10.0)]);
+        restarted.transactions.push(blockchain.transactions[0].clone());
+        restarted.rebuild_pending_index();
+
+        assert_eq!(restarted.import_mempool(&mempool), Ok(1)); // the first transaction was already pending
+        assert_eq!(restarted.transactions, blockchain.transactions);
+        assert_eq!(restarted.import_mempool(&mempool[..mempool.len() - 1]), Err(MempoolError::Malformed));
+    }
+
+    #[test]
+    fn rejected_block_is_reported_by_import_mempool() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(2);
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let mempool = blockchain.export_mempool();
+
+        let mut restarted = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut restarted, &[(&sender, 10.0)]);
+
+        let genesis = &restarted.chain[0];
+        let future = Block::build(1, genesis.hash(), Vec::new(), None, Utc::now() + chrono::Duration::hours(1), DIFFICULTY);
+
+        restarted.chain.push(future);
+        restarted.index += 1;
+
+        assert_eq!(restarted.import_mempool(&mempool), Err(MempoolError::Chain(ChainError::NonMonotonicTime(2))));
+        assert_eq!(restarted.pending_transactions().len(), 1); // the transaction was imported
+    }
+
+    #[test]
+    fn observer_is_notified_of_the_events() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Counter {
+            validated: AtomicUsize,
+            rejected: AtomicUsize,
+            mined: AtomicUsize,
+        }
+
+        struct CountingObserver(Arc<Counter>);
+
+        impl ChainObserver for CountingObserver {
+            fn on_transaction_validated(&self, _transaction: &Transaction) {
+                self.0.validated.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn on_transaction_rejected(&self, _error: &ValidationError) {
+                self.0.rejected.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn on_block_mined(&self, _block: &Block) {
+                self.0.mined.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let counter = Arc::new(Counter::default());
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(2);
+        blockchain.set_observer(Box::new(CountingObserver(Arc::clone(&counter))));
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "wrong_password").unwrap_err();
+        blockchain.push_transaction(&mut sender, &mut receiver, 20.0, "sender_password").unwrap_err();
+        blockchain.push_transaction(&mut sender, &mut receiver, 3.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        assert_eq!(counter.validated.load(Ordering::SeqCst), 3);
+        assert_eq!(counter.rejected.load(Ordering::SeqCst), 2);
+        assert_eq!(counter.mined.load(Ordering::SeqCst), 1);
+
+        blockchain.flush().unwrap();
+
+        assert_eq!(counter.mined.load(Ordering::SeqCst), 2);
+
+        let mut unobserved = blockchain.clone();
+        unobserved.observer = Observer::default();
+
+        assert_eq!(unobserved, blockchain); // the observer isn't compared
+    }
+
+    #[test]
+    fn blockchain_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<BlockChain>();
+    }
+
+    #[test]
+    fn sealed_block_takes_the_pending_transactions() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(3);
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        let pending = blockchain.pending_transactions().to_vec();
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 3.0, "sender_password").unwrap();
+
+        let sealed = blockchain.last_block().transactions();
+
+        assert_eq!(blockchain.len(), 2);
+        assert_eq!(sealed.len(), 3);
+        assert_eq!(sealed[..2], pending[..]);
+        assert_eq!(sealed[2].amount(), 3.0);
+        assert!(blockchain.pending_transactions().is_empty());
+    }
+
+    #[test]
+    fn cached_balances_match_a_full_scan() {
+        fn scanned_balance(blockchain: &BlockChain, address: &str) -> f64 {
+            let rewards: f64 = blockchain.chain
+                .iter()
+                .filter_map(|block| block.coinbase())
+                .map(|coinbase| coinbase.reward_of(address))
+                .sum();
+
+            let transactions: f64 = blockchain.chain
+                .iter()
+                .flat_map(|block| block.transactions())
+                .chain(&blockchain.transactions)
+                .map(|t| BlockChain::balance_change(t, address))
+                .sum();
+
+            (rewards + transactions).max(0.0)
+        }
+
+        fn scanned_immature_rewards(blockchain: &BlockChain, address: &str) -> f64 {
+            blockchain.chain
+                .iter()
+                .filter(|block| block.index() > 0 && blockchain.index - block.index() < blockchain.coinbase_maturity)
+                .filter_map(|block| block.coinbase())
+                .map(|coinbase| coinbase.reward_of(address))
+                .sum()
+        }
+
+        fn scanned_next_nonce(blockchain: &BlockChain, address: &str) -> u64 {
+            blockchain.chain
+                .iter()
+                .flat_map(|block| block.transactions())
+                .chain(&blockchain.transactions)
+                .filter(|t| t.sender.address() == address && t.reversal_of().is_none())
+                .map(|t| t.nonce() + 1)
+                .max()
+                .unwrap_or(0)
+        }
+
+        let mut first = Account::new("First", "Account", "first_password");
+        let mut second = Account::new("Second", "Account", "second_password");
+        let mut third = Account::new("Third", "Account", "third_password");
+        first.add_money(50.0).unwrap();
+
+        let mut blockchain = BlockChain::new(3, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&first, 50.0)]);
+        blockchain.set_miner(&third.address(), 8.0).unwrap();
+        blockchain.set_coinbase_maturity(2);
+
+        let addresses = [first.address(), second.address(), third.address()];
+        let check = |blockchain: &BlockChain| {
+            for address in &addresses {
+                assert_eq!(blockchain.balance_of(address).value(), scanned_balance(blockchain, address));
+                assert_eq!(blockchain.immature_rewards(address), scanned_immature_rewards(blockchain, address));
+                assert_eq!(blockchain.next_nonce(address), scanned_next_nonce(blockchain, address));
+            }
+        };
+
+        blockchain.push_transaction(&mut first, &mut second, 10.0, "first_password").unwrap();
+        check(&blockchain);
+
+        blockchain.push_transaction(&mut second, &mut third, 4.0, "second_password").unwrap();
+        blockchain.push_transaction(&mut first, &mut third, 2.5, "first_password").unwrap(); // the block is sealed
+        check(&blockchain);
+
+        blockchain.push_transaction(&mut third, &mut first, 6.0, "third_password").unwrap();
+        check(&blockchain);
+
+        blockchain.flush().unwrap();
+        check(&blockchain);
+
+        blockchain.push_transaction(&mut second, &mut first, 1.5, "second_password").unwrap();
+        blockchain.rollback(1).unwrap();
+        check(&blockchain);
+
+        blockchain.rebuild_indexes();
+        check(&blockchain);
+
+        blockchain.set_coinbase_maturity(1);
+        check(&blockchain);
+    }
+
+    #[test]
+    fn mine_block_seals_the_queued_transactions() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(10);
+
+        let empty = blockchain.mine_block().unwrap();
+
+        assert_eq!(empty.index(), 1);
+        assert!(empty.transactions().is_empty());
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        let queued = blockchain.pending_transactions().to_vec();
+        let block = blockchain.mine_block().unwrap();
+
+        assert_eq!(block.index(), 2);
+        assert_eq!(block.transactions(), &queued[..]);
+        assert!(blockchain.pending_transactions().is_empty());
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 3.0);
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn custom_genesis_credits_its_allocations() {
+        let mut founder = Account::new("Founder", "Account", "founder_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+
+        let coinbase = Coinbase::new(&founder.address(), PositiveF64::new(1000.0).unwrap());
+        let genesis = Block::with_coinbase(0, [0; 64], Vec::new(), coinbase, DIFFICULTY);
+
+        let mut blockchain = BlockChain::with_genesis(1, genesis.clone()).unwrap();
+
+        assert_eq!(blockchain.chain[0], genesis);
+        assert_eq!(blockchain.balance_of(&founder.address()).value(), 1000.0);
+        assert_eq!(blockchain.total_supply().value(), 1000.0);
+
+        founder.add_money(1000.0).unwrap();
+        blockchain.push_transaction(&mut founder, &mut receiver, 250.0, "founder_password").unwrap();
+
+        assert_eq!(blockchain.balance_of(&founder.address()).value(), 750.0);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 250.0);
+        assert_eq!(blockchain.rollback(2), Err(ChainError::InvalidRollback(2)));
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        let linked = Block::new(0, genesis.hash(), Vec::new(), DIFFICULTY);
+
+        assert_eq!(BlockChain::with_genesis(1, linked), Err(ChainError::InvalidBlock(0, BlockError::InvalidPrevHash)));
+    }
+
+    #[test]
+    fn rollback_drops_the_last_block_and_its_balances() {
+        let admin = Account::new("Admin", "Account", "admin_password");
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, 0);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+        blockchain.set_admin(admin.public_key());
+        blockchain.set_target_block_time(Duration::from_secs(60), 2);
+
+        for amount in [1.0, 2.0, 3.0] {
+            blockchain.push_transaction(&mut sender, &mut receiver, amount, "sender_password").unwrap();
+        }
+
+        let (len, index) = (blockchain.len(), blockchain.index);
+        let hash = blockchain.chain[3].transactions()[0].hash();
+        let admin_key = admin.keypair()[..32].try_into().unwrap();
+
+        assert_eq!(len, 4);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 6.0);
+        assert_eq!(blockchain.current_difficulty(), 0);
+
+        blockchain.chargeback(&hash, &admin_key).unwrap();
+
+        assert!(blockchain.charged_back.contains(&hash));
+        assert_eq!(blockchain.current_difficulty(), 1);
+
+        // removing the reversal makes the transaction reversible again, and restores the difficulty
+        let removed = blockchain.rollback(1).unwrap();
+
+        assert_eq!(removed[0].transactions()[0].reversal_of(), Some(hash));
+        assert!(blockchain.charged_back.is_empty());
+        assert_eq!(blockchain.current_difficulty(), 0);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 6.0);
+        assert_eq!(blockchain.chargeback(&hash, &admin_key), Ok(()));
+        assert_eq!(blockchain.current_difficulty(), 1);
+
+        let removed = blockchain.rollback(2).unwrap();
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].transactions()[0].amount(), 3.0);
+        assert_eq!(blockchain.len(), len - 1);
+        assert_eq!(blockchain.index, index - 1);
+        assert_eq!(blockchain.balance_of(&receiver.address()).value(), 3.0);
+        assert_eq!(blockchain.current_difficulty(), 0);
+        assert_eq!(blockchain.rollback(3), Err(ChainError::InvalidRollback(3)));
+        assert_eq!(blockchain.len(), len - 1);
+    }
+
+    #[test]
+    fn difficulty_follows_the_block_times() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, 0);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+        blockchain.set_target_block_time(Duration::from_secs(60), 2);
+
+        for _ in 0..3 {
+            blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        }
+
+        assert_eq!(blockchain.current_difficulty(), 0); // the genesis block isn't counted
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.current_difficulty(), 1);
+        assert_eq!(blockchain.adjusted_difficulty(MAX_DIFFICULTY, 4), MAX_DIFFICULTY); // never raised above the maximum
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.chain[5].difficulty(), 1);
+
+        for index in 6..=8 {
+            let last = blockchain.last_block();
+            let slow = Block::build(index, last.hash(), Vec::new(), None, last.time() + chrono::Duration::minutes(5), 1);
+
+            blockchain.append_block(slow).unwrap();
+        }
+
+        assert_eq!(blockchain.current_difficulty(), 0); // blocks #6 and #8 were mined 10 minutes apart
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+    }
+
+    #[test]
+    fn total_supply_is_the_premine_plus_rewards() {
+        let mut first = Account::new("First", "Account", "first_password");
+        let mut second = Account::new("Second", "Account", "second_password");
+        let mut miner = Account::new("Miner", "Account", "miner_password");
+        first.add_money(100.0).unwrap();
+        second.add_money(50.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&first, 100.0), (&second, 50.0)]);
+
+        assert_eq!(blockchain.total_supply().value(), 150.0);
+
+        blockchain.set_miner(&miner.address(), 25.0).unwrap();
+        blockchain.push_transaction(&mut first, &mut second, 30.0, "first_password").unwrap();
+        blockchain.push_transaction(&mut second, &mut first, 10.0, "second_password").unwrap();
+
+        assert_eq!(blockchain.total_supply().value(), 200.0);
+
+        blockchain.collect_rewards(&mut miner);
+
+        assert_eq!(blockchain.total_supply().value(), 200.0); // the rewards are only moved off the chain
+
+        blockchain.push_transaction(&mut miner, &mut first, 5.0, "miner_password").unwrap();
+
+        assert_eq!(blockchain.total_supply().value(), 225.0);
+    }
+
+    #[test]
+    fn pending_transactions_wait_for_the_next_block() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(2);
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.pending_transactions().len(), 1);
+        assert_eq!(blockchain.pending_transactions()[0].receiver.address(), receiver.address());
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 3.0, "sender_password").unwrap();
+
+        assert!(blockchain.pending_transactions().is_empty());
+        assert_eq!(blockchain.len(), 2);
+    }
+
+    #[test]
+    fn pending_for_lists_only_unconfirmed_transactions() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut other = Account::new("Other", "Account", "other_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+        other.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(3, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0), (&other, 10.0)]);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut other, &mut receiver, 2.0, "other_password").unwrap();
+
+        let pending = blockchain.pending_for(&sender.address());
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].hash(), blockchain.transactions[0].hash());
+        assert_eq!(blockchain.pending_for(&receiver.address()).len(), 2);
+
+        blockchain.push_transaction(&mut sender, &mut other, 1.0, "sender_password").unwrap(); // the block is sealed
+
+        assert!(blockchain.pending_for(&sender.address()).is_empty());
+        assert!(blockchain.pending_for(&receiver.address()).is_empty());
+    }
+
+    #[test]
+    fn block_reward_halves_until_zero() {
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+
+        assert_eq!(blockchain.block_reward(1_000).value(), 50.0); // no halving by default
+
+        blockchain.set_halving_interval(4).unwrap();
+
+        assert_eq!(blockchain.block_reward(0).value(), 50.0);
+        assert_eq!(blockchain.block_reward(3).value(), 50.0);
+        assert_eq!(blockchain.block_reward(4).value(), 25.0);
+        assert_eq!(blockchain.block_reward(8).value(), 12.5);
+        assert_eq!(blockchain.block_reward(12).value(), 6.25);
+        assert!(blockchain.block_reward(4 * 40).is_zero());
+        assert!(blockchain.block_reward(usize::MAX).is_zero());
+    }
+
+    #[test]
+    fn state_merkle_root_depends_only_on_balances() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let (mut other_sender, mut other_receiver) = (sender.clone(), receiver.clone());
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        let mut other = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut other, &[(&other_sender, 10.0)]);
+        other.push_transaction(&mut other_sender, &mut other_receiver, 3.0, "sender_password").unwrap();
+        other.push_transaction(&mut other_sender, &mut other_receiver, 2.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.state_merkle_root(), other.state_merkle_root());
+
+        other.push_transaction(&mut other_sender, &mut other_receiver, 1.0, "sender_password").unwrap();
+
+        assert_ne!(blockchain.state_merkle_root(), other.state_merkle_root());
+    }
+
+    #[test]
+    fn canonical_bytes_change_with_the_chain() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 5.0, "sender_password").unwrap();
+
+        let copy = blockchain.clone();
+
+        assert_eq!(blockchain.canonical_bytes(), copy.canonical_bytes());
+
+        let mut extended = blockchain.clone();
+        extended.append_block(Block::new(2, blockchain.chain[1].hash(), Vec::new(), DIFFICULTY)).unwrap();
+
+        assert_ne!(blockchain.canonical_bytes(), extended.canonical_bytes());
+
+        let mut tampered = blockchain.clone();
+        let transaction = Transaction::new(sender, receiver, 1.0, "sender_password").unwrap();
+        tampered.chain[1] = Block::build(1, blockchain.chain[0].hash(), vec![transaction], None, blockchain.chain[1].time(), DIFFICULTY);
+
+        assert_ne!(blockchain.canonical_bytes(), tampered.canonical_bytes());
+    }
+
+    #[test]
+    fn transactions_above_threshold_with_their_blocks() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(100.0).unwrap();
+
+        let mut blockchain = BlockChain::new(2, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 100.0)]);
+
+        for amount in &[5.0, 40.0, 10.0, 30.0] {
+            blockchain.push_transaction(&mut sender, &mut receiver, *amount, "sender_password").unwrap();
+        }
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 15.0, "sender_password").unwrap(); // still pending
+
+        let above: Vec<(usize, f64)> = blockchain
+            .transactions_above(10.0)
+            .into_iter()
+            .map(|(index, t)| (index, t.amount()))
+            .collect();
+
+        assert_eq!(above, vec![(1, 40.0), (2, 30.0)]);
+    }
+
+    #[test]
+    fn mempool_digest_is_order_independent() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let first = Transaction::new(sender.clone(), receiver.clone(), 5.0, "sender_password").unwrap();
+        let second = Transaction::new(sender, receiver, 2.0, "sender_password").unwrap();
+
+        let mut blockchain = BlockChain::new(3, DIFFICULTY);
+        blockchain.transactions.push(first.clone());
+        blockchain.transactions.push(second.clone());
+
+        let mut other = BlockChain::new(3, DIFFICULTY);
+        other.transactions.push(second);
+
+        assert_ne!(blockchain.mempool_digest(), other.mempool_digest());
+
+        other.transactions.push(first);
+
+        assert_eq!(blockchain.mempool_digest(), other.mempool_digest());
+    }
+
+    #[test]
+    fn chargeback_restores_the_balances() {
+        let admin = Account::new("Admin", "Account", "admin_password");
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.set_admin(admin.public_key());
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let hash = blockchain.chain[1].transactions()[0].hash();
+        let admin_key = admin.keypair()[..32].try_into().unwrap();
+
+        assert_eq!(blockchain.balances[&receiver.address()], 4.0);
+        assert_eq!(blockchain.chargeback(&[0; 64], &admin_key), Err(ChargebackError::UnknownTransaction));
+        assert_eq!(blockchain.chargeback(&hash, &admin_key), Ok(()));
+
+        let reversal = &blockchain.chain[2].transactions()[0];
+
+        assert_eq!(reversal.sender.address(), receiver.address());
+        assert_eq!(reversal.receiver.address(), sender.address());
+        assert_eq!(reversal.amount(), 4.0);
+        assert_eq!(reversal.reversal_of(), Some(hash));
+        assert_eq!(blockchain.balances[&sender.address()], 10.0);
+        assert_eq!(blockchain.balances[&receiver.address()], 0.0);
+        assert_eq!(blockchain.chargeback(&hash, &admin_key), Err(ChargebackError::AlreadyReversed));
+        assert_eq!(blockchain.is_valid(), Ok(()));
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        // the account of the receiver still holds the money, but the chain doesn't
+        assert_eq!(receiver.balance(), 4.0);
+        assert_eq!(
+            blockchain.push_transaction(&mut receiver, &mut sender, 4.0, "receiver_password"),
+            Err(PushError::Invalid(ValidationError::InvalidAmount)),
+        );
+    }
+
+    #[test]
+    fn chargeback_of_spent_funds_is_rejected() {
+        let admin = Account::new("Admin", "Account", "admin_password");
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        let mut other = Account::new("Other", "Account", "other_password");
+
+        blockchain.set_admin(admin.public_key());
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut receiver, &mut other, 3.0, "receiver_password").unwrap();
+
+        let hash = blockchain.chain[1].transactions()[0].hash();
+        let admin_key = admin.keypair()[..32].try_into().unwrap();
+
+        assert_eq!(blockchain.chargeback(&hash, &admin_key), Err(ChargebackError::InsufficientFunds));
+        assert_eq!(blockchain.len(), 3);
+    }
+
+    #[test]
+    fn reversal_not_signed_by_the_admin_is_rejected() {
+        let admin = Account::new("Admin", "Account", "admin_password");
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.set_admin(admin.public_key());
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let forger = Keypair::from_bytes(&receiver.keypair()).unwrap();
+        let forged = blockchain.chain[1].transactions()[0].reversed(&forger);
+
+        assert_eq!(forged.validate(forged.hash()), Err(ValidationError::InvalidSignature));
+
+        let block = Block::new(2, blockchain.chain[1].hash(), vec![forged], DIFFICULTY);
+
+        blockchain.append_block(block).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidTransaction(2, ValidationError::InvalidSignature)));
+    }
+
+    #[test]
+    fn benchmark_hashrate_runs_for_the_duration() {
+        let duration = Duration::from_millis(200);
+        let start = Instant::now();
+
+        let hashrate = BlockChain::benchmark_hashrate(duration);
+        let elapsed = start.elapsed();
+
+        assert!(hashrate > 0.0 && hashrate.is_finite());
+        assert!(elapsed >= duration);
+    }
+
+    #[test]
+    fn reward_is_split_between_payouts() {
+        let mut blockchain = BlockChain::new(5, DIFFICULTY);
+        blockchain.set_miner("pool_address", 50.0).unwrap();
+
+        let mismatched = [(String::from("first_miner"), 30.0), (String::from("second_miner"), 10.0)];
+
+        assert_eq!(blockchain.seal_block_with_payouts(&mismatched), Err(PayoutError::Mismatch { expected: 50.0, actual: 40.0 }));
+        assert_eq!(blockchain.seal_block_with_payouts(&[(String::from("first_miner"), -50.0)]), Err(PayoutError::InvalidShare));
+        assert_eq!(blockchain.index, 0);
+
+        let payouts = [(String::from("first_miner"), 37.5), (String::from("second_miner"), 12.5)];
+
+        assert_eq!(blockchain.seal_block_with_payouts(&payouts), Ok(()));
+        assert_eq!(blockchain.balances["first_miner"], 37.5);
+        assert_eq!(blockchain.balances["second_miner"], 12.5);
+        assert_eq!(blockchain.spendable_rewards("second_miner"), 12.5);
+        assert_eq!(blockchain.blocks_by_miner("second_miner").len(), 1);
+        assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+    }
+
+    #[test]
+    fn transactions_must_be_covered_by_the_chain_balance() {
+        let sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        fund_in_genesis(&mut blockchain, &[(&sender, 10.0)]);
+
+        let covered = Transaction::with_fee(sender.clone(), receiver.clone(), 9.0, 1.0, "sender_password").unwrap();
+        blockchain.append_block(Block::new(1, blockchain.chain[0].hash(), vec![covered], DIFFICULTY)).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        blockchain.rollback(1).unwrap();
+
+        // the transaction doesn't know the balance of the sender, only the chain does
+        let overdrawn = Transaction::with_fee(sender, receiver, 9.0, 1.5, "sender_password").unwrap();
+
+        assert_eq!(overdrawn.validate(overdrawn.hash()), Ok(()));
+
+        blockchain.append_block(Block::new(1, blockchain.chain[0].hash(), vec![overdrawn], DIFFICULTY)).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidTransaction(1, ValidationError::InvalidAmount)));
+    }
+
+    #[test]
+    fn inflated_coinbase_makes_the_chain_invalid() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+        blockchain.seal_block_with_payouts(&[(String::from("first_miner"), 30.0), (String::from("second_miner"), 20.0)]).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Ok(()));
+
+        let coinbase = Coinbase::new("miner_address", PositiveF64::new(1000.0).unwrap());
+        let inflated = Block::with_coinbase(3, blockchain.chain[2].hash(), Vec::new(), coinbase, DIFFICULTY);
+
+        blockchain.append_block(inflated).unwrap();
+
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidCoinbase(3)));
+    }
+
+    #[test]
+    fn duplicated_transaction_is_found() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 2.0, "sender_password").unwrap();
+
+        assert!(blockchain.find_duplicates().is_empty());
+
+        let duplicated = blockchain.chain[1].transactions().to_vec();
+        let hash = duplicated[0].hash();
+
+        blockchain.chain.push(Block::new(3, blockchain.chain[2].hash(), duplicated, DIFFICULTY));
+
+        assert_eq!(blockchain.find_duplicates(), vec![hash]);
+        assert_eq!(blockchain.validate_chain(), Err(ChainError::InvalidBlock(3, BlockError::DuplicateTransaction)));
+    }
+
+    #[test]
+    fn block_time_variance_of_regular_and_irregular_blocks() {
+        let start = Utc::now();
+        let times_at = |seconds: &[i64]| -> Vec<DateTime<Utc>> {
+            seconds.iter().map(|s| start + chrono::Duration::seconds(*s)).collect()
+        };
+
+        let even = BlockChain::intervals_variance(&times_at(&[0, 10, 20, 30, 40])).unwrap();
+        let irregular = BlockChain::intervals_variance(&times_at(&[0, 1, 30, 32, 90])).unwrap();
+
+        assert!(even.abs() < 1e-9);
+        assert!(irregular > even);
+        assert_eq!(BlockChain::intervals_variance(&times_at(&[0, 10])), None);
+    }
+
+    #[test]
+    fn supply_invariant_detects_injected_credit() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        let miner = Account::new("Miner", "Account", "miner_password");
+
+        blockchain.set_miner(&miner.address(), 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+
+        *blockchain.balances.get_mut(&receiver.address()).unwrap() += 100.0;
+
+        assert_eq!(blockchain.verify_supply_invariant(), Err(SupplyError::Mismatch { expected: 60.0, actual: 160.0 }));
+    }
+
+    #[test]
+    fn supply_invariant_detects_money_from_nowhere() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+
+        // the coinbase and the balances agree with each other, but not with the reward schedule
+        let coinbase = Coinbase::new("miner_address", PositiveF64::new(1000.0).unwrap());
+        let inflated = Block::with_coinbase(2, blockchain.chain[1].hash(), Vec::new(), coinbase, DIFFICULTY);
+
+        blockchain.append_block(inflated).unwrap();
+
+        assert_eq!(blockchain.verify_supply_invariant(), Err(SupplyError::InvalidCoinbase(2)));
+    }
+
+    #[test]
+    fn rewards_are_spendable_after_maturity() {
+        let (mut blockchain, mut sender, mut receiver) = funded_chain(1);
+        let mut miner = Account::new("Miner", "Account", "miner_password");
+
+        blockchain.set_miner(&miner.address(), 50.0).unwrap();
+        blockchain.set_coinbase_maturity(2);
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.collect_rewards(&mut miner), 0.0);
+        assert_eq!(blockchain.immature_rewards(&miner.address()), 100.0);
+
+        assert_eq!(blockchain.push_transaction(&mut miner, &mut receiver, 1.0, "miner_password"), Err(PushError::Invalid(ValidationError::InvalidAmount))); // the miner can't spend yet
+
+        assert_eq!(blockchain.chain.len(), 3);
+
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        assert_eq!(blockchain.spendable_rewards(&miner.address()), 50.0); // only block #1 is mature
+        assert_eq!(blockchain.collect_rewards(&mut miner), 50.0);
+
+        blockchain.push_transaction(&mut miner, &mut receiver, 30.0, "miner_password").unwrap();
+
+        assert_eq!(miner.balance(), 20.0);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::block::DIFFICULTY;
+
+    fn saved_chain(name: &str) -> (BlockChain, std::path::PathBuf) {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.premine(vec![(sender.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+        blockchain.set_miner("miner_address", 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+
+        let path = std::env::temp_dir().join(name);
+
+        blockchain.save_to_file(&path).unwrap();
+
+        (blockchain, path)
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let (blockchain, path) = saved_chain("round_trip_blockchain.json");
+
+        let loaded = BlockChain::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded.chain.len(), 2);
+        assert_eq!(loaded, blockchain);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn tampered_file_is_rejected() {
+        let (_, path) = saved_chain("tampered_blockchain.json");
+
+        let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        json["chain"][1]["transactions"][0]["amount"] = serde_json::json!(9.0);
+
+        fs::write(&path, json.to_string()).unwrap();
+
+        assert_eq!(
+            BlockChain::load_from_file(&path),
+            Err(LoadError::Invalid(ChainError::InvalidTransaction(1, ValidationError::Tempered))),
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loaded_state_is_checked_again() {
+        let (_, path) = saved_chain("unchecked_blockchain.json");
+
+        let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        json["transactions"] = serde_json::json!([json["chain"][1]["transactions"][0].clone()]); // already in the chain
+        json["validated_height"] = serde_json::json!(0);
+        json["current_difficulty"] = serde_json::json!(64);
+
+        fs::write(&path, json.to_string()).unwrap();
+
+        let loaded = BlockChain::load_from_file(&path).unwrap();
+
+        assert!(loaded.pending_transactions().is_empty());
+        assert_eq!(loaded.validated_height(), 1);
+        assert_eq!(loaded.current_difficulty(), DIFFICULTY);
+
+        json["difficulty"] = serde_json::json!(64);
+
+        fs::write(&path, json.to_string()).unwrap();
+
+        assert!(matches!(BlockChain::load_from_file(&path), Err(LoadError::Parse(_))));
+
+        fs::remove_file(path).unwrap();
     }
 }
@@ -0,0 +1,129 @@
+use crate::positive_f64::PositiveF64;
+
+/// A structure to handle the reward given to whoever mined a block.
+/// 
+/// Every coinbase contains the payouts of the block, each made of:
+/// - the address of a miner
+/// - the part of the reward credited to the miner
+/// 
+/// Usually the whole reward goes to a single miner, but it can be split
+/// between the miners of a pool.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coinbase {
+    payouts: Vec<(String, PositiveF64)>,
+}
+
+impl Coinbase {
+    /// Generates a new `Coinbase`, which credits the whole reward to the miner.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(50.0).unwrap());
+    /// 
+    /// assert_eq!(coinbase.reward(), 50.0);
+    /// ```
+    pub fn new(miner: &str, reward: PositiveF64) -> Self {
+        Self {
+            payouts: vec![(String::from(miner), reward)],
+        }
+    }
+
+    /// Generates a new `Coinbase`, which splits the reward between the miners of the payouts.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::with_payouts(vec![
+    ///     (String::from("first_miner"), PositiveF64::new(30.0).unwrap()),
+    ///     (String::from("second_miner"), PositiveF64::new(20.0).unwrap()),
+    /// ]);
+    /// 
+    /// assert_eq!(coinbase.reward(), 50.0);
+    /// assert_eq!(coinbase.reward_of("second_miner"), 20.0);
+    /// ```
+    pub fn with_payouts(payouts: Vec<(String, PositiveF64)>) -> Self {
+        Self { payouts }
+    }
+
+    /// This method returns the address of the miner who sealed the block,
+    /// which is the first address of the payouts, if any.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(50.0).unwrap());
+    /// 
+    /// assert_eq!(coinbase.miner(), "miner_address");
+    /// ```
+    pub fn miner(&self) -> &str {
+        self.payouts.first().map_or("", |(miner, _)| miner)
+    }
+
+    /// This method returns the whole reward of the block, which is the sum of the payouts.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(12.5).unwrap());
+    /// 
+    /// assert_eq!(coinbase.reward(), 12.5);
+    /// ```
+    pub fn reward(&self) -> f64 {
+        self.payouts.iter().map(|(_, reward)| reward).sum::<PositiveF64>().value()
+    }
+
+    /// This method returns the payouts of the coinbase, since the `payouts` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(12.5).unwrap());
+    /// 
+    /// assert_eq!(coinbase.payouts(), [(String::from("miner_address"), PositiveF64::new(12.5).unwrap())]);
+    /// ```
+    pub fn payouts(&self) -> &[(String, PositiveF64)] {
+        &self.payouts
+    }
+
+    /// This method checks if the address is credited by any payout of the coinbase.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(12.5).unwrap());
+    /// 
+    /// assert!(coinbase.pays("miner_address"));
+    /// assert!(!coinbase.pays("another_address"));
+    /// ```
+    pub fn pays(&self, address: &str) -> bool {
+        self.payouts.iter().any(|(miner, _)| miner == address)
+    }
+
+    /// This method returns the part of the reward credited to the address.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::coinbase::Coinbase;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let coinbase = Coinbase::new("miner_address", PositiveF64::new(12.5).unwrap());
+    /// 
+    /// assert_eq!(coinbase.reward_of("miner_address"), 12.5);
+    /// assert_eq!(coinbase.reward_of("another_address"), 0.0);
+    /// ```
+    pub fn reward_of(&self, address: &str) -> f64 {
+        self.payouts
+            .iter()
+            .filter(|(miner, _)| miner == address)
+            .map(|(_, reward)| reward)
+            .sum::<PositiveF64>()
+            .value()
+    }
+}
@@ -0,0 +1,237 @@
+use std::{fmt, error, fs, io};
+use std::path::Path;
+use serde::Deserialize;
+use crate::{
+    blockchain::BlockChain,
+    block::{DIFFICULTY, MAX_DIFFICULTY},
+    positive_f64::PositiveF64,
+};
+
+/// The configuration of a network, as read from a TOML file.
+/// 
+/// Every field but `transactions_per_block` is optional.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    transactions_per_block: usize,
+    difficulty: Option<usize>,
+    network_id: Option<String>,
+    miner: Option<String>,
+    reward: Option<f64>,
+    halving_interval: Option<usize>,
+    coinbase_maturity: Option<usize>,
+    #[serde(default)]
+    premine: Vec<Allocation>,
+}
+
+/// An amount of money credited to an address in the genesis block.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Allocation {
+    address: String,
+    amount: f64,
+}
+
+impl BlockChain {
+    /// Generates a new `BlockChain` from the configuration of a network, read from a TOML file.
+    /// 
+    /// The configuration has the following layout, where every field but `transactions_per_block` is optional:
+    /// ```toml
+    /// transactions_per_block = 5
+    /// difficulty = 2
+    /// network_id = "mainnet"
+    /// miner = "<the address of the miner>"
+    /// reward = 50.0
+    /// halving_interval = 210000
+    /// coinbase_maturity = 100
+    /// 
+    /// [[premine]]
+    /// address = "<the address credited in the genesis block>"
+    /// amount = 1000.0
+    /// ```
+    /// 
+    /// The premine allocations are credited by the `Coinbase` of the genesis block.
+    /// 
+    /// The function returns a `Result<BlockChain, ConfigError>`, because
+    /// if the file can't be read, a `ConfigError::Io` error is returned,
+    /// if the file isn't a valid configuration, a `ConfigError::Parse` error is returned,
+    /// and if a field has a value which can't be used, a `ConfigError::InvalidValue` error is returned:
+    /// - `transactions_per_block` must not be zero
+    /// - `difficulty` can't be greater than `MAX_DIFFICULTY`; if it's missing, `DIFFICULTY` is used
+    /// - `reward` must be positive, and it must be given together with `miner`
+    /// - the `amount` of every `premine` allocation must be positive
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::config::ConfigError;
+    /// let path = std::env::temp_dir().join("gallifrey.toml");
+    /// 
+    /// std::fs::write(&path, "transactions_per_block = 3\ndifficulty = 1\nnetwork_id = \"gallifrey\"\n").unwrap();
+    /// 
+    /// let blockchain = BlockChain::from_config_file(&path).unwrap();
+    /// 
+    /// assert_eq!(blockchain.transactions_per_block(), 3);
+    /// assert_eq!(blockchain.difficulty(), 1);
+    /// assert_eq!(blockchain.network_id(), Some("gallifrey"));
+    /// 
+    /// std::fs::write(&path, "transactions_per_block = 0\n").unwrap();
+    /// 
+    /// assert_eq!(BlockChain::from_config_file(&path), Err(ConfigError::InvalidValue("transactions_per_block")));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_config_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|e| ConfigError::Io(e.kind()))?;
+        let config: Config = toml::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string()))?;
+
+        if config.transactions_per_block == 0 {
+            return Err(ConfigError::InvalidValue("transactions_per_block"));
+        }
+
+        let difficulty = config.difficulty.unwrap_or(DIFFICULTY);
+
+        if difficulty > MAX_DIFFICULTY {
+            return Err(ConfigError::InvalidValue("difficulty"));
+        }
+
+        let premine = config.premine
+            .into_iter()
+            .map(|allocation| match PositiveF64::new(allocation.amount) {
+                Ok(amount) if amount.value() > 0.0 => Ok((allocation.address, amount)),
+                _ => Err(ConfigError::InvalidValue("premine")),
+            })
+            .collect::<Result<Vec<(String, PositiveF64)>, ConfigError>>()?;
+
+        let mut blockchain = BlockChain::new(config.transactions_per_block, difficulty);
+
+        match (config.miner, config.reward) {
+            (Some(miner), reward) => blockchain
+                .set_miner(&miner, reward.unwrap_or(0.0))
+                .map_err(|_| ConfigError::InvalidValue("reward"))?,
+            (None, Some(_)) => return Err(ConfigError::InvalidValue("miner")),
+            (None, None) => {},
+        }
+
+        if let Some(network_id) = config.network_id {
+            blockchain.set_network_id(&network_id);
+        }
+
+        if let Some(halving_interval) = config.halving_interval {
+            blockchain
+                .set_halving_interval(halving_interval)
+                .map_err(|_| ConfigError::InvalidValue("halving_interval"))?;
+        }
+
+        if let Some(coinbase_maturity) = config.coinbase_maturity {
+            blockchain.set_coinbase_maturity(coinbase_maturity);
+        }
+
+        if !premine.is_empty() {
+            blockchain.premine(premine).expect("Error crediting the premine to the genesis block of a new chain.");
+        }
+
+        Ok(blockchain)
+    }
+}
+
+/// An enum to handle errors generated while reading the configuration of a `BlockChain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Io(io::ErrorKind),
+    Parse(String),
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "Couldn't read the configuration file ({:?}).", kind),
+            Self::Parse(message) => write!(f, "Invalid configuration file: {}.", message),
+            Self::InvalidValue(field) => write!(f, "Invalid value for `{}` in the configuration file.", field),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn valid_config_builds_the_chain() {
+        let path = write_config("valid_config.toml", r#"
+            transactions_per_block = 4
+            difficulty = 2
+            network_id = "testnet"
+            miner = "miner_address"
+            reward = 50.0
+            halving_interval = 10
+            coinbase_maturity = 5
+
+            [[premine]]
+            address = "first_address"
+            amount = 1000.0
+
+            [[premine]]
+            address = "second_address"
+            amount = 250.0
+        "#);
+
+        let blockchain = BlockChain::from_config_file(&path).unwrap();
+        let genesis = &blockchain.chain()[0];
+
+        assert_eq!(blockchain.transactions_per_block(), 4);
+        assert_eq!(blockchain.difficulty(), 2);
+        assert_eq!(blockchain.network_id(), Some("testnet"));
+        assert_eq!(blockchain.block_reward(1).value(), 50.0);
+        assert_eq!(blockchain.block_reward(10).value(), 25.0);
+        assert_eq!(genesis.coinbase().unwrap().reward_of("first_address"), 1000.0);
+        assert_eq!(genesis.coinbase().unwrap().reward_of("second_address"), 250.0);
+        assert_eq!(blockchain.verify_supply_invariant(), Ok(()));
+        assert_eq!(blockchain.is_valid(), Ok(()));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid_config_is_described() {
+        let missing = write_config("missing_field.toml", "network_id = \"testnet\"\n");
+
+        match BlockChain::from_config_file(&missing) {
+            Err(ConfigError::Parse(message)) => assert!(message.contains("transactions_per_block")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let negative = write_config("negative_premine.toml", r#"
+            transactions_per_block = 4
+
+            [[premine]]
+            address = "first_address"
+            amount = -10.0
+        "#);
+
+        assert_eq!(BlockChain::from_config_file(&negative), Err(ConfigError::InvalidValue("premine")));
+
+        let unminable = write_config("unminable_difficulty.toml", "transactions_per_block = 4\ndifficulty = 64\n");
+
+        assert_eq!(BlockChain::from_config_file(&unminable), Err(ConfigError::InvalidValue("difficulty")));
+        assert_eq!(
+            BlockChain::from_config_file(Path::new("/nonexistent/config.toml")),
+            Err(ConfigError::Io(io::ErrorKind::NotFound)),
+        );
+
+        fs::remove_file(missing).unwrap();
+        fs::remove_file(negative).unwrap();
+        fs::remove_file(unminable).unwrap();
+    }
+}
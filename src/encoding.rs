@@ -0,0 +1,107 @@
+use std::convert::TryInto;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// A structure to write the fields of the structures of the crate as bytes.
+/// 
+/// Every number is written in little-endian order, and every string or vector
+/// is prefixed with its length, so that the encoding is the same on every platform.
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn array(&mut self, value: &[u8]) {
+        self.bytes.extend_from_slice(value);
+    }
+
+    pub(crate) fn string(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    pub(crate) fn time(&mut self, value: DateTime<Utc>) {
+        self.bytes.extend_from_slice(&value.timestamp().to_le_bytes());
+        self.bytes.extend_from_slice(&value.timestamp_subsec_nanos().to_le_bytes());
+    }
+}
+
+/// A structure to read back the fields written by a `Writer`;
+/// every method returns `None` if the bytes are not enough or malformed.
+#[derive(Debug)]
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            None
+        } else {
+            let (taken, rest) = self.bytes.split_at(len);
+
+            self.bytes = rest;
+
+            Some(taken)
+        }
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn f64(&mut self) -> Option<f64> {
+        self.take(8).map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N).map(|b| b.try_into().unwrap())
+    }
+
+    pub(crate) fn string(&mut self) -> Option<String> {
+        let len = self.u64()?.try_into().ok()?;
+
+        self.take(len).and_then(|b| String::from_utf8(b.to_vec()).ok())
+    }
+
+    pub(crate) fn time(&mut self) -> Option<DateTime<Utc>> {
+        let seconds = i64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        let nanoseconds = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+
+        Utc.timestamp_opt(seconds, nanoseconds).single()
+    }
+}
+
+/// This function encodes the bytes as a lowercase hex string.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// This function decodes the hex string, and returns `None` if it isn't valid hex.
+#[cfg(any(feature = "keystore", feature = "serde"))]
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect()
+}
@@ -0,0 +1,245 @@
+use std::{fmt, error};
+use std::convert::TryInto;
+use sha2::{Sha512, Digest};
+use hmac::Hmac;
+use aes::{
+    Aes128Ctr,
+    cipher::{NewCipher, StreamCipher},
+};
+use rand::{RngCore, rngs::OsRng};
+use serde_json::{json, Value};
+use crate::{
+    account::{Account, constant_time_eq},
+    encoding::{Writer, Reader, to_hex, from_hex},
+};
+
+/// The number of iterations of PBKDF2 used to derive the key from the passphrase.
+pub const KEYSTORE_ITERATIONS: u32 = 100_000;
+
+/// The cipher used to encrypt the account.
+const CIPHER: &str = "aes-128-ctr";
+
+/// The key derivation function used to derive the key from the passphrase.
+const KDF: &str = "pbkdf2";
+
+/// The pseudorandom function used by PBKDF2.
+const PRF: &str = "hmac-sha512";
+
+impl Account {
+    /// This method exports the account as a JSON keystore, encrypted with the passphrase.
+    /// 
+    /// The keystore has the following layout:
+    /// ```json
+    /// {
+    ///     "version": 1,
+    ///     "address": "<the address of the account>",
+    ///     "crypto": {
+    ///         "cipher": "aes-128-ctr",
+    ///         "cipherparams": { "iv": "<hex>" },
+    ///         "ciphertext": "<hex>",
+    ///         "kdf": "pbkdf2",
+    ///         "kdfparams": { "c": 100000, "dklen": 32, "prf": "hmac-sha512", "salt": "<hex>" },
+    ///         "mac": "<hex>"
+    ///     }
+    /// }
+    /// ```
+    /// 
+    /// A 32 bytes key is derived from the passphrase and the salt using PBKDF2:
+    /// the first 16 bytes are used to encrypt the whole account with AES-128 in CTR mode,
+    /// and the `mac` is the SHA-512 hash of the last 16 bytes followed by the `ciphertext`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// let mut kate = Account::new("Kate", "Stewart", "UNIT_Kate_Stewart");
+    /// kate.add_money(10.0).unwrap();
+    /// 
+    /// let keystore = kate.to_keystore_json("correct horse battery staple");
+    /// 
+    /// assert_eq!(Account::from_keystore_json(&keystore, "correct horse battery staple"), Ok(kate));
+    /// ```
+    pub fn to_keystore_json(&self, passphrase: &str) -> String {
+        let mut salt = [0; 32];
+        let mut iv = [0; 16];
+
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut iv);
+
+        let key = derive_key(passphrase, &salt, KEYSTORE_ITERATIONS);
+
+        let mut writer = Writer::default();
+
+        self.encode(&mut writer);
+
+        let mut ciphertext = writer.bytes();
+
+        Aes128Ctr::new(key[..16].into(), (&iv).into()).apply_keystream(&mut ciphertext);
+
+        json!({
+            "version": 1,
+            "address": self.address(),
+            "crypto": {
+                "cipher": CIPHER,
+                "cipherparams": { "iv": to_hex(&iv) },
+                "ciphertext": to_hex(&ciphertext),
+                "kdf": KDF,
+                "kdfparams": {
+                    "c": KEYSTORE_ITERATIONS,
+                    "dklen": 32,
+                    "prf": PRF,
+                    "salt": to_hex(&salt),
+                },
+                "mac": to_hex(&mac(&key, &ciphertext)),
+            },
+        }).to_string()
+    }
+
+    /// This method imports an account from a JSON keystore generated by `.to_keystore_json()`,
+    /// decrypting it with the passphrase.
+    /// 
+    /// The function returns a `Result<Account, KeystoreError>`, because
+    /// if the JSON doesn't follow the layout of the keystore, a `KeystoreError::Malformed` error is returned,
+    /// if the cipher or the key derivation function aren't supported, or the number of iterations
+    /// isn't `KEYSTORE_ITERATIONS`, a `KeystoreError::Unsupported` error is returned,
+    /// and if the `mac` doesn't match, which happens if the passphrase is wrong,
+    /// a `KeystoreError::InvalidMac` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::account::Account;
+    /// # use blockchain::keystore::KeystoreError;
+    /// let osgood = Account::new("Petronella", "Osgood", "Osgood_Scarf_2013");
+    /// 
+    /// let keystore = osgood.to_keystore_json("correct horse battery staple");
+    /// 
+    /// assert_eq!(Account::from_keystore_json(&keystore, "wrong passphrase"), Err(KeystoreError::InvalidMac));
+    /// assert_eq!(Account::from_keystore_json("{}", "correct horse battery staple"), Err(KeystoreError::Malformed));
+    /// ```
+    pub fn from_keystore_json(json: &str, passphrase: &str) -> Result<Self, KeystoreError> {
+        let keystore: Value = serde_json::from_str(json).map_err(|_| KeystoreError::Malformed)?;
+        let crypto = &keystore["crypto"];
+
+        if crypto["cipher"] != CIPHER || crypto["kdf"] != KDF || crypto["kdfparams"]["prf"] != PRF || crypto["kdfparams"]["dklen"] != 32 {
+            return Err(if crypto.is_object() { KeystoreError::Unsupported } else { KeystoreError::Malformed });
+        }
+
+        let field = |value: &Value| value.as_str().and_then(from_hex).ok_or(KeystoreError::Malformed);
+
+        let iv: [u8; 16] = field(&crypto["cipherparams"]["iv"])?.try_into().map_err(|_| KeystoreError::Malformed)?;
+        let salt = field(&crypto["kdfparams"]["salt"])?;
+        let mut plaintext = field(&crypto["ciphertext"])?;
+        let expected_mac = field(&crypto["mac"])?;
+
+        // the iteration count comes from the file, so it's not trusted:
+        // a huge count would hang the import, and a tiny one would weaken the key
+        match crypto["kdfparams"]["c"].as_u64() {
+            Some(c) if c == u64::from(KEYSTORE_ITERATIONS) => {},
+            Some(_) => return Err(KeystoreError::Unsupported),
+            None => return Err(KeystoreError::Malformed),
+        }
+
+        let key = derive_key(passphrase, &salt, KEYSTORE_ITERATIONS);
+
+        if !constant_time_eq(&mac(&key, &plaintext), &expected_mac) {
+            return Err(KeystoreError::InvalidMac);
+        }
+
+        Aes128Ctr::new(key[..16].into(), (&iv).into()).apply_keystream(&mut plaintext);
+
+        let mut reader = Reader::new(&plaintext);
+
+        match Account::decode(&mut reader) {
+            Some(account) if reader.is_empty() => Ok(account),
+            _ => Err(KeystoreError::Malformed),
+        }
+    }
+}
+
+/// This function derives the 32 bytes key from the passphrase using PBKDF2.
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0; 32];
+
+    pbkdf2::pbkdf2::<Hmac<Sha512>>(passphrase.as_bytes(), salt, iterations, &mut key);
+
+    key
+}
+
+/// This function computes the `mac` of the ciphertext, which is the SHA-512 hash
+/// of the last 16 bytes of the key followed by the ciphertext.
+fn mac(key: &[u8; 32], ciphertext: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+
+    hasher.update(&key[16..]);
+    hasher.update(ciphertext);
+
+    hasher
+        .finalize()[..]
+        .try_into()
+        .expect("Error generating the SHA-512 hash of the keystore.")
+}
+
+/// An enum to handle errors generated while importing an `Account` from a JSON keystore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoreError {
+    Malformed,
+    Unsupported,
+    InvalidMac,
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Malformed => write!(f, "Malformed keystore."),
+            Self::Unsupported => write!(f, "Unsupported keystore cipher or key derivation function."),
+            Self::InvalidMac => write!(f, "Invalid keystore MAC, the passphrase may be wrong."),
+        }
+    }
+}
+
+impl error::Error for KeystoreError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::Transaction;
+
+    #[test]
+    fn keystore_round_trip_restores_a_working_account() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let keystore = sender.to_keystore_json("passphrase");
+
+        assert_eq!(Account::from_keystore_json(&keystore, "wrong_passphrase"), Err(KeystoreError::InvalidMac));
+
+        let mut restored = Account::from_keystore_json(&keystore, "passphrase").unwrap();
+
+        assert_eq!(restored, sender);
+        assert!(restored.verify_password("sender_password"));
+
+        let transaction = Transaction::new(restored.clone(), receiver.clone(), 4.0, "sender_password").unwrap();
+
+        assert_eq!(transaction.validate(transaction.hash()), Ok(()));
+        assert_eq!(restored.transfer(&mut receiver, 4.0), Ok(()));
+        assert_eq!(restored.balance(), 6.0);
+    }
+
+    #[test]
+    fn keystores_with_other_iteration_counts_are_rejected() {
+        let account = Account::new("Sender", "Account", "sender_password");
+        let keystore = account.to_keystore_json("passphrase");
+
+        for c in &[0, 1, u64::from(u32::MAX)] {
+            let mut tampered: Value = serde_json::from_str(&keystore).unwrap();
+            tampered["crypto"]["kdfparams"]["c"] = json!(c);
+
+            assert_eq!(Account::from_keystore_json(&tampered.to_string(), "passphrase"), Err(KeystoreError::Unsupported));
+        }
+
+        let mut tampered: Value = serde_json::from_str(&keystore).unwrap();
+        tampered["crypto"]["kdfparams"]["c"] = json!("many");
+
+        assert_eq!(Account::from_keystore_json(&tampered.to_string(), "passphrase"), Err(KeystoreError::Malformed));
+    }
+}
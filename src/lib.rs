@@ -2,7 +2,15 @@ pub mod account;
 pub mod positive_f64;
 pub mod transaction;
 pub mod block;
+pub mod coinbase;
 pub mod blockchain;
+pub mod proof;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+#[cfg(feature = "config")]
+pub mod config;
+mod encoding;
+mod merkle;
 
 #[cfg(test)]
 mod tests {
@@ -1,22 +1,43 @@
 use blockchain::{
-    blockchain::BlockChain,
+    blockchain::{BlockChain, ChainObserver},
+    block::{Block, DIFFICULTY},
     account::Account,
+    positive_f64::PositiveF64,
 };
 
+/// An observer which prints the blocks mined by the chain.
+struct Logger;
+
+impl ChainObserver for Logger {
+    fn on_block_mined(&self, block: &Block) {
+        println!("{}", block);
+    }
+}
+
 fn main() {
-    let mut a0 = Account::new("a", "a", "a");
+    let mut a0 = Account::new_with_balance("a", "a", "a", 100.0).unwrap();
     let mut a1 = Account::new("b", "b", "b");
-    let mut a2 = Account::new("c", "c", "c");
+    let mut a2 = Account::new_with_balance("c", "c", "c", 100.0).unwrap();
     let mut a3 = Account::new("d", "d", "d");
-    let mut a4 = Account::new("e", "e", "e");
-    
-    a0.add_money(100.0);
-    a2.add_money(100.0);
-    a4.add_money(100.0);
+    let a4 = Account::new_with_balance("e", "e", "e", 100.0).unwrap();
+
+    let mut blockchain = BlockChain::new(2, DIFFICULTY);
+    blockchain.premine(
+        [&a0, &a2, &a4]
+            .iter()
+            .map(|account| (account.address(), PositiveF64::new(account.balance()).unwrap()))
+            .collect(),
+    ).unwrap();
+    blockchain.set_observer(Box::new(Logger));
 
-    let mut blockchain = BlockChain::new(2);
-    blockchain.push_transaction(&mut a0, &mut a1, 2.0, "a");
-    blockchain.push_transaction(&mut a2, &mut a3, 1.0, "c");
+    for result in [
+        blockchain.push_transaction(&mut a0, &mut a1, 2.0, "a"),
+        blockchain.push_transaction(&mut a2, &mut a3, 1.0, "c"),
+    ] {
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+    }
 
     println!("{} {} {} {} {}", a0, a1, a2, a3, a4);
 }
@@ -0,0 +1,104 @@
+use std::convert::TryInto;
+use sha2::{Sha512, Digest};
+
+/// The byte prepended to the leaves of a Merkle tree before hashing them.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// The byte prepended to the concatenation of two nodes of a Merkle tree before hashing them,
+/// so that a node can't be passed off as a leaf, nor a leaf as a node.
+const NODE_PREFIX: u8 = 0x01;
+
+/// This function computes the SHA-512 hash of the bytes of a leaf of a Merkle tree.
+fn hash_leaf(bytes: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(bytes);
+
+    hasher
+        .finalize()[..]
+        .try_into()
+        .expect("Error generating the SHA-512 hash of the Merkle leaf.")
+}
+
+/// This function computes the SHA-512 hash of the concatenation of two nodes of a Merkle tree.
+pub(crate) fn hash_pair(left: &[u8; 64], right: &[u8; 64]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher
+        .finalize()[..]
+        .try_into()
+        .expect("Error generating the SHA-512 hash of the Merkle node.")
+}
+
+/// This function computes the root of the Merkle tree built over the hashes of the leaves;
+/// if a level has an odd number of nodes, the last node is paired with itself,
+/// and the root of an empty tree is made of zeros.
+/// 
+/// Since pairing the last node with itself gives the same root to the leaves `[a, b, c]` and `[a, b, c, c]`,
+/// the callers must reject duplicated leaves.
+pub(crate) fn merkle_root<T: AsRef<[u8]>>(leaves: &[T]) -> [u8; 64] {
+    if leaves.is_empty() {
+        return [0; 64];
+    }
+
+    let mut level = leaf_level(leaves);
+
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+
+    level[0]
+}
+
+/// This function computes the bottom level of a Merkle tree, made of the hashes of the leaves.
+fn leaf_level<T: AsRef<[u8]>>(leaves: &[T]) -> Vec<[u8; 64]> {
+    leaves.iter().map(|leaf| hash_leaf(leaf.as_ref())).collect()
+}
+
+/// This function computes the nodes of a Merkle tree above the given level,
+/// pairing the last node with itself if the level has an odd number of nodes.
+fn parent_level(level: &[[u8; 64]]) -> Vec<[u8; 64]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.last().unwrap()))
+        .collect()
+}
+
+/// This function returns the siblings of the nodes on the path from the leaf at `index` to the root
+/// of the Merkle tree built over the leaves, starting from the sibling of the leaf;
+/// each sibling comes with `true` if it's the left node of its pair, and `false` otherwise.
+pub(crate) fn merkle_path<T: AsRef<[u8]>>(leaves: &[T], mut index: usize) -> Vec<([u8; 64], bool)> {
+    let mut path = Vec::new();
+    let mut level = leaf_level(leaves);
+
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+
+        // the last node of an odd level is paired with itself
+        path.push((*level.get(sibling).unwrap_or(&level[index]), sibling < index));
+
+        level = parent_level(&level);
+        index /= 2;
+    }
+
+    path
+}
+
+/// This function computes the root of a Merkle tree from a leaf and the siblings on its path,
+/// as returned by `merkle_path()`.
+pub(crate) fn root_from_path(leaf: &[u8], path: &[([u8; 64], bool)]) -> [u8; 64] {
+    path
+        .iter()
+        .fold(hash_leaf(leaf), |node, (sibling, is_left)| {
+            if *is_left {
+                hash_pair(sibling, &node)
+            } else {
+                hash_pair(&node, sibling)
+            }
+        })
+}
@@ -2,33 +2,76 @@ use std::{
     fmt,
     error,
     ops,
+    num::ParseFloatError,
+    str::FromStr,
+    iter::Sum,
 };
 
 /// A struct to handle positive `f64` numbers.
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// 
+/// `PositiveF64`s can be compared with each other, by comparing their values.
+/// 
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::PositiveF64;
+/// assert!(PositiveF64::new(3.0).unwrap() > PositiveF64::new(1.0).unwrap());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct PositiveF64(f64);
 
 impl PositiveF64 {
     /// Generates a new `PositiveF64`.
     /// 
     /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
-    /// if the number is negative, an `InvalidNumber::NegativeValue` error is returned.
+    /// if the number is NaN or infinite, an `InvalidNumber::NotFinite` error is returned,
+    /// and if the number is negative, an `InvalidNumber::NegativeValue` error is returned.
     /// 
     /// # Example
     /// ```
-    /// # use blockchain::positive_f64::PositiveF64;
+    /// # use blockchain::positive_f64::{PositiveF64, InvalidNumber};
     /// let positive_f64 = PositiveF64::new(3.0).unwrap();
     /// 
     /// assert_eq!(positive_f64.value(), 3.0); // this method returns the value
+    /// 
+    /// assert_eq!(PositiveF64::new(-3.0), Err(InvalidNumber::NegativeValue));
+    /// assert_eq!(PositiveF64::new(f64::NAN), Err(InvalidNumber::NotFinite));
+    /// assert_eq!(PositiveF64::new(f64::INFINITY), Err(InvalidNumber::NotFinite));
     /// ```
     pub fn new(number: f64) -> Result<Self, InvalidNumber> {
-        if number >= 0.0 {
+        if !number.is_finite() {
+            Err(InvalidNumber::NotFinite)
+        } else if number >= 0.0 {
             Ok(PositiveF64(number))
         } else {
             Err(InvalidNumber::NegativeValue)
         }
     }
 
+    /// Returns a `PositiveF64` equal to `0.0`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let zero = PositiveF64::zero();
+    /// 
+    /// assert_eq!(zero.value(), 0.0);
+    /// ```
+    pub const fn zero() -> Self {
+        PositiveF64(0.0)
+    }
+
+    /// This method checks if the number is equal to `0.0`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// assert!(PositiveF64::zero().is_zero());
+    /// assert!(!PositiveF64::new(1.0).unwrap().is_zero());
+    /// ```
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+
     /// This method returns the value of the number, since the value in the struct isn't `pub`.
     /// 
     /// # Example
@@ -71,6 +114,131 @@ impl PositiveF64 {
     pub unsafe fn new_unchecked(number: f64) -> Self {
         PositiveF64(number)
     }
+
+    /// Subtracts another `PositiveF64` from the number, without panicking.
+    /// 
+    /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
+    /// if the difference is negative, an `InvalidNumber::NegativeValue` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let five = PositiveF64::new(5.0).unwrap();
+    /// 
+    /// assert_eq!(five.checked_sub(PositiveF64::new(2.0).unwrap()).unwrap().value(), 3.0);
+    /// assert!(five.checked_sub(PositiveF64::new(7.0).unwrap()).is_err());
+    /// ```
+    pub fn checked_sub(self, other: Self) -> Result<Self, InvalidNumber> {
+        PositiveF64::new(self.0 - other.0)
+    }
+
+    /// Subtracts another `PositiveF64` from the number, returning `0.0` if the difference is negative.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let five = PositiveF64::new(5.0).unwrap();
+    /// 
+    /// assert_eq!(five.saturating_sub(PositiveF64::new(2.0).unwrap()).value(), 3.0);
+    /// assert_eq!(five.saturating_sub(PositiveF64::new(7.0).unwrap()).value(), 0.0);
+    /// ```
+    pub fn saturating_sub(self, other: Self) -> Self {
+        self.checked_sub(other).unwrap_or(PositiveF64::zero())
+    }
+
+    /// Returns the maximum between the number and another `PositiveF64`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let three = PositiveF64::new(3.0).unwrap();
+    /// let one = PositiveF64::new(1.0).unwrap();
+    /// 
+    /// assert_eq!(three.max(one), three);
+    /// ```
+    pub fn max(self, other: Self) -> Self {
+        if self >= other {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Returns the minimum between the number and another `PositiveF64`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let three = PositiveF64::new(3.0).unwrap();
+    /// let one = PositiveF64::new(1.0).unwrap();
+    /// 
+    /// assert_eq!(three.min(one), one);
+    /// ```
+    pub fn min(self, other: Self) -> Self {
+        if self <= other {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Adds another `PositiveF64` to the number, without panicking.
+    /// 
+    /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
+    /// if the sum overflows, an `InvalidNumber::NotFinite` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::{PositiveF64, InvalidNumber};
+    /// let three = PositiveF64::new(3.0).unwrap();
+    /// let max = PositiveF64::new(f64::MAX).unwrap();
+    /// 
+    /// assert_eq!(three.checked_add(PositiveF64::new(2.0).unwrap()).unwrap().value(), 5.0);
+    /// assert_eq!(max.checked_add(max), Err(InvalidNumber::NotFinite));
+    /// ```
+    pub fn checked_add(self, other: Self) -> Result<Self, InvalidNumber> {
+        PositiveF64::new(self.0 + other.0)
+    }
+
+    /// Multiplies the number by another `PositiveF64`, without panicking.
+    /// 
+    /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
+    /// if the product overflows, an `InvalidNumber::NotFinite` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::{PositiveF64, InvalidNumber};
+    /// let three = PositiveF64::new(3.0).unwrap();
+    /// 
+    /// assert_eq!(three.checked_mul(PositiveF64::new(2.0).unwrap()).unwrap().value(), 6.0);
+    /// assert_eq!(PositiveF64::new(f64::MAX).unwrap().checked_mul(three), Err(InvalidNumber::NotFinite));
+    /// ```
+    pub fn checked_mul(self, other: Self) -> Result<Self, InvalidNumber> {
+        PositiveF64::new(self.0 * other.0)
+    }
+
+    /// Divides the number by another `PositiveF64`, without panicking.
+    /// 
+    /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
+    /// if the divisor is `0.0`, an `InvalidNumber::DivisionByZero` error is returned,
+    /// and if the quotient overflows, an `InvalidNumber::NotFinite` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::{PositiveF64, InvalidNumber};
+    /// let six = PositiveF64::new(6.0).unwrap();
+    /// 
+    /// assert_eq!(six.checked_div(PositiveF64::new(2.0).unwrap()).unwrap().value(), 3.0);
+    /// assert!(six.checked_div(PositiveF64::new(0.0).unwrap()).is_err());
+    /// assert_eq!(PositiveF64::new(f64::MAX).unwrap().checked_div(PositiveF64::new(0.5).unwrap()), Err(InvalidNumber::NotFinite));
+    /// ```
+    pub fn checked_div(self, other: Self) -> Result<Self, InvalidNumber> {
+        if other.is_zero() {
+            Err(InvalidNumber::DivisionByZero)
+        } else {
+            PositiveF64::new(self.0 / other.0)
+        }
+    }
 }
 
 impl fmt::Display for PositiveF64 {
@@ -79,11 +247,48 @@ impl fmt::Display for PositiveF64 {
     }
 }
 
+/// A `PositiveF64` can be parsed from a string, and the parsed number is checked by `PositiveF64::new()`.
+/// 
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::{PositiveF64, InvalidNumber, ParsePositiveF64Error};
+/// let number: PositiveF64 = "3.5".parse().unwrap();
+/// 
+/// assert_eq!(number.value(), 3.5);
+/// 
+/// assert_eq!("-2.0".parse::<PositiveF64>(), Err(ParsePositiveF64Error::Invalid(InvalidNumber::NegativeValue)));
+/// assert!(matches!("abc".parse::<PositiveF64>(), Err(ParsePositiveF64Error::Parse(_))));
+/// ```
+impl FromStr for PositiveF64 {
+    type Err = ParsePositiveF64Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s.parse::<f64>().map_err(ParsePositiveF64Error::Parse)?;
+
+        PositiveF64::new(number).map_err(ParsePositiveF64Error::Invalid)
+    }
+}
+
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::PositiveF64;
+/// let sum = PositiveF64::new(2.0).unwrap() + PositiveF64::new(3.0).unwrap();
+/// 
+/// assert_eq!(sum.value(), 5.0);
+/// ```
+/// 
+/// # Panics
+/// A sum that overflows makes the program panic, use `.checked_add()` to handle it.
+/// 
+/// ```should_panic
+/// # use blockchain::positive_f64::PositiveF64;
+/// let _ = PositiveF64::new(f64::MAX).unwrap() + PositiveF64::new(f64::MAX).unwrap();
+/// ```
 impl ops::Add for PositiveF64 {
     type Output = PositiveF64;
 
     fn add(self, other: Self) -> Self {
-        PositiveF64(self.0 + other.0)
+        self.checked_add(other).unwrap()
     }
 }
 
@@ -91,38 +296,241 @@ impl ops::Sub for PositiveF64 {
     type Output = PositiveF64;
 
     fn sub(self, other: Self) -> Self {
-        let _ = PositiveF64::new(self.0 - other.0).unwrap(); // if the difference is >= 0.0
+        self.checked_sub(other).unwrap() // if the difference is >= 0.0
+    }
+}
 
-        PositiveF64::new(self.0 - other.0).unwrap()
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::PositiveF64;
+/// let product = PositiveF64::new(2.0).unwrap() * PositiveF64::new(3.0).unwrap();
+/// 
+/// assert_eq!(product.value(), 6.0);
+/// ```
+/// 
+/// # Panics
+/// A product that overflows makes the program panic, use `.checked_mul()` to handle it.
+/// 
+/// ```should_panic
+/// # use blockchain::positive_f64::PositiveF64;
+/// let _ = PositiveF64::new(f64::MAX).unwrap() * PositiveF64::new(2.0).unwrap();
+/// ```
+impl ops::Mul for PositiveF64 {
+    type Output = PositiveF64;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other).unwrap()
+    }
+}
+
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::PositiveF64;
+/// let quotient = PositiveF64::new(6.0).unwrap() / PositiveF64::new(2.0).unwrap();
+/// 
+/// assert_eq!(quotient.value(), 3.0);
+/// ```
+/// 
+/// # Panics
+/// Dividing by `0.0`, or a quotient that overflows, makes the program panic, use `.checked_div()` to handle it.
+/// 
+/// ```should_panic
+/// # use blockchain::positive_f64::PositiveF64;
+/// let _ = PositiveF64::new(6.0).unwrap() / PositiveF64::new(0.0).unwrap();
+/// ```
+impl ops::Div for PositiveF64 {
+    type Output = PositiveF64;
+
+    fn div(self, other: Self) -> Self {
+        self.checked_div(other).unwrap()
     }
 }
 
 impl ops::AddAssign for PositiveF64 {
     fn add_assign(&mut self, other: Self) {
-        self.0 += other.0;
+        *self = self.checked_add(other).unwrap();
     }
 }
 
 impl ops::SubAssign for PositiveF64 {
     fn sub_assign(&mut self, other: Self) {
-        let _ = PositiveF64::new(self.0 - other.0).unwrap(); // if the difference is >= 0.0
+        *self = self.checked_sub(other).unwrap(); // if the difference is >= 0.0
+    }
+}
+
+/// An iterator of `PositiveF64`s can be summed, starting from `PositiveF64::zero()`.
+/// 
+/// # Example
+/// ```
+/// # use blockchain::positive_f64::PositiveF64;
+/// let numbers = vec![PositiveF64::new(1.0).unwrap(), PositiveF64::new(2.0).unwrap(), PositiveF64::new(3.0).unwrap()];
+/// 
+/// assert_eq!(numbers.iter().copied().sum::<PositiveF64>().value(), 6.0);
+/// assert_eq!(numbers.iter().sum::<PositiveF64>().value(), 6.0);
+/// ```
+impl Sum for PositiveF64 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(PositiveF64::zero(), |acc, n| acc + n)
+    }
+}
+
+impl<'a> Sum<&'a PositiveF64> for PositiveF64 {
+    fn sum<I: Iterator<Item = &'a PositiveF64>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl ops::MulAssign for PositiveF64 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.checked_mul(other).unwrap();
+    }
+}
 
-        self.0 -= other.0;
+impl ops::DivAssign for PositiveF64 {
+    fn div_assign(&mut self, other: Self) {
+        *self = self.checked_div(other).unwrap();
     }
 }
 
 /// An enum to handle invalid `PositiveF64` numbers.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvalidNumber {
     NegativeValue,
+    NotFinite,
+    DivisionByZero,
 }
 
 impl fmt::Display for InvalidNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Self::NegativeValue => write!(f, "The number can't be negative."),
+            Self::NotFinite => write!(f, "The number must be finite."),
+            Self::DivisionByZero => write!(f, "The number can't be divided by zero."),
         }
     }
 }
 
 impl error::Error for InvalidNumber {}
+
+/// An enum to handle errors generated while parsing a `PositiveF64` from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePositiveF64Error {
+    Parse(ParseFloatError),
+    Invalid(InvalidNumber),
+}
+
+impl fmt::Display for ParsePositiveF64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "The string isn't a number: {}.", e),
+            Self::Invalid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for ParsePositiveF64Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Invalid(e) => Some(e),
+        }
+    }
+}
+
+/// A `PositiveF64` is serialized as its value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PositiveF64 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+/// A `PositiveF64` is deserialized through `PositiveF64::new()`,
+/// so that invalid numbers are rejected.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PositiveF64 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let number = f64::deserialize(deserializer)?;
+
+        PositiveF64::new(number).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl PositiveF64 {
+    /// Converts the number into a `rust_decimal::Decimal`, for exact arithmetic;
+    /// `None` is returned if the number is too large to be represented as a `Decimal`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let number = PositiveF64::new(2.5).unwrap();
+    /// 
+    /// assert_eq!(number.to_decimal().unwrap().to_string(), "2.5");
+    /// ```
+    pub fn to_decimal(&self) -> Option<rust_decimal::Decimal> {
+        use rust_decimal::prelude::FromPrimitive;
+
+        rust_decimal::Decimal::from_f64(self.0)
+    }
+
+    /// Generates a new `PositiveF64` from a `rust_decimal::Decimal`.
+    /// 
+    /// The function returns a `Result<PositiveF64, InvalidNumber>`, because
+    /// if the number is negative, an `InvalidNumber::NegativeValue` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// # use rust_decimal::Decimal;
+    /// let number = PositiveF64::try_from_decimal(Decimal::new(25, 1)).unwrap(); // 2.5
+    /// 
+    /// assert_eq!(number.value(), 2.5);
+    /// ```
+    pub fn try_from_decimal(decimal: rust_decimal::Decimal) -> Result<Self, InvalidNumber> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        PositiveF64::new(decimal.to_f64().ok_or(InvalidNumber::NotFinite)?)
+    }
+}
+
+#[cfg(all(test, feature = "rust_decimal"))]
+mod decimal_tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn decimal_round_trip() {
+        let number = PositiveF64::new(42.125).unwrap();
+
+        let decimal = number.to_decimal().unwrap();
+
+        assert_eq!(decimal, Decimal::new(42125, 3));
+        assert_eq!(PositiveF64::try_from_decimal(decimal), Ok(number));
+    }
+
+    #[test]
+    fn negative_decimal_is_rejected() {
+        assert_eq!(PositiveF64::try_from_decimal(Decimal::new(-1, 0)), Err(InvalidNumber::NegativeValue));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let number = PositiveF64::new(42.5).unwrap();
+
+        let json = serde_json::to_string(&number).unwrap();
+
+        assert_eq!(json, "42.5");
+        assert_eq!(serde_json::from_str::<PositiveF64>(&json).unwrap(), number);
+    }
+
+    #[test]
+    fn negative_json_is_rejected() {
+        assert!(serde_json::from_str::<PositiveF64>("-1.0").is_err());
+    }
+}
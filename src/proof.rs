@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+use chrono::{DateTime, Utc};
+use crate::{
+    block::Block,
+    transaction::Transaction,
+    coinbase::Coinbase,
+    merkle,
+};
+
+/// A structure to prove the balance of an address to a light client,
+/// which only knows the hashes of the blocks of the chain.
+/// 
+/// For every block containing transactions involving the address, or whose `Coinbase` pays the address,
+/// the proof contains:
+/// - the index, the previous hash, the Merkle root, the `Coinbase`, the time, the difficulty and the nonce of the block
+/// - the transactions involving the address, each with its Merkle path to the root of the block
+/// 
+/// Note: the proof shows that its transactions and rewards are in the chain, but not that they're all
+/// the transactions and rewards of the address: a proof which leaves out some blocks, or some transactions,
+/// is still valid, so the balance is only as complete as the chain which generated the proof.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceProof {
+    address: String,
+    blocks: Vec<BlockProof>,
+}
+
+/// The siblings of the nodes on the path from a transaction to the Merkle root of its block,
+/// each with `true` if it's the left node of its pair.
+type MerklePath = Vec<([u8; 64], bool)>;
+
+/// The part of a `BalanceProof` related to a single block.
+#[derive(Debug, Clone, PartialEq)]
+struct BlockProof {
+    index: usize,
+    prev_hash: [u8; 64],
+    merkle_root: [u8; 64],
+    coinbase: Option<Coinbase>,
+    time: DateTime<Utc>,
+    difficulty: usize,
+    nonce: u128,
+    transactions: Vec<(Transaction, MerklePath)>,
+}
+
+impl BalanceProof {
+    /// Generates a new `BalanceProof` for the address, from the blocks of the chain.
+    pub(crate) fn new(address: &str, chain: &[Block]) -> Self {
+        let blocks = chain
+            .iter()
+            .filter_map(|block| {
+                let transactions_hashes: Vec<[u8; 64]> = block.transactions().iter().map(|t| t.hash()).collect();
+
+                let transactions: Vec<(Transaction, MerklePath)> = block
+                    .transactions()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| t.sender.address() == address || t.receiver.address() == address)
+                    .map(|(index, t)| (t.clone(), merkle::merkle_path(&transactions_hashes, index)))
+                    .collect();
+
+                let is_paid = block.coinbase().is_some_and(|coinbase| coinbase.reward_of(address) > 0.0);
+
+                if transactions.is_empty() && !is_paid {
+                    None
+                } else {
+                    Some(BlockProof {
+                        index: block.index(),
+                        prev_hash: block.prev_hash(),
+                        merkle_root: block.merkle_root(),
+                        coinbase: block.coinbase().cloned(),
+                        time: block.time(),
+                        difficulty: block.difficulty(),
+                        nonce: block.nonce(),
+                        transactions,
+                    })
+                }
+            })
+            .collect();
+
+        Self {
+            address: String::from(address),
+            blocks,
+        }
+    }
+
+    /// This method returns the address the proof refers to, since the `address` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// let martha = Account::new("Martha", "Jones", "MarthaJ_2007");
+    /// 
+    /// let blockchain = BlockChain::new(1, DIFFICULTY);
+    /// let proof = blockchain.balance_proof(&martha.address());
+    /// 
+    /// assert_eq!(proof.address(), martha.address());
+    /// ```
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// This method checks the proof against the hashes of the blocks of the chain,
+    /// and returns the balance of the address if the proof is valid, `None` otherwise.
+    /// 
+    /// The balance is the sum of the amounts received by the address, plus the rewards paid to the address
+    /// by the `Coinbase` of the blocks, minus the sum of the amounts and the fees it sent.
+    /// 
+    /// The proof isn't valid if:
+    /// - the hash of a block, recomputed from the proof, doesn't match with the hash in `headers`
+    /// - the Merkle path of a transaction, starting from its hash recomputed from its fields,
+    ///   doesn't lead to the Merkle root of its block
+    /// - a transaction doesn't involve the address, or is included more than once
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::blockchain::BlockChain;
+    /// # use blockchain::account::Account;
+    /// # use blockchain::block::DIFFICULTY;
+    /// # use blockchain::positive_f64::PositiveF64;
+    /// let mut rose = Account::new("Rose", "Tyler", "rose_tyler_2005");
+    /// let mut mickey = Account::new("Mickey", "Smith", "M1ckey!Smith");
+    /// rose.add_money(30.0).unwrap();
+    /// 
+    /// let mut blockchain = BlockChain::new(1, DIFFICULTY);
+    /// blockchain.premine(vec![(rose.address(), PositiveF64::new(30.0).unwrap())]).unwrap();
+    /// blockchain.push_transaction(&mut rose, &mut mickey, 12.0, "rose_tyler_2005").unwrap();
+    /// 
+    /// let headers: Vec<[u8; 64]> = blockchain.iter().map(|b| b.hash()).collect();
+    /// let proof = blockchain.balance_proof(&mickey.address());
+    /// 
+    /// assert_eq!(proof.verify(&headers), Some(12.0));
+    /// ```
+    pub fn verify(&self, headers: &[[u8; 64]]) -> Option<f64> {
+        let mut balance = 0.0;
+        let mut seen = HashSet::new();
+
+        for block in &self.blocks {
+            let hash = Block::hash_fields(block.index, block.prev_hash, &block.merkle_root, block.coinbase.as_ref(), block.time, block.difficulty, block.nonce);
+
+            if headers.get(block.index) != Some(&hash) {
+                return None;
+            }
+
+            if let Some(coinbase) = &block.coinbase {
+                balance += coinbase.reward_of(&self.address);
+            }
+
+            for (transaction, path) in &block.transactions {
+                let transaction_hash = transaction.compute_hash();
+
+                if merkle::root_from_path(&transaction_hash, path) != block.merkle_root || !seen.insert(transaction_hash) {
+                    return None;
+                }
+
+                if transaction.receiver.address() == self.address {
+                    balance += transaction.amount();
+                } else if transaction.sender.address() == self.address {
+                    balance -= transaction.amount() + transaction.fee();
+                } else {
+                    return None;
+                }
+            }
+        }
+
+        Some(balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        account::Account,
+        blockchain::BlockChain,
+        block::DIFFICULTY,
+        positive_f64::PositiveF64,
+        transaction::Transaction,
+    };
+
+    #[test]
+    fn altered_transaction_fails_verification() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut middle = Account::new("Middle", "Account", "middle_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.premine(vec![(sender.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+        blockchain.push_transaction(&mut sender, &mut middle, 10.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut middle, &mut receiver, 4.0, "middle_password").unwrap();
+
+        let headers: Vec<[u8; 64]> = blockchain.iter().map(|b| b.hash()).collect();
+        let mut proof = blockchain.balance_proof(&middle.address());
+
+        assert_eq!(proof.verify(&headers), Some(6.0));
+
+        proof.blocks[1].transactions[0].0 = Transaction::new(middle.clone(), receiver, 1.0, "middle_password").unwrap();
+
+        assert_eq!(proof.verify(&headers), None);
+    }
+
+    #[test]
+    fn rewards_are_counted_in_the_balance() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let mut receiver = Account::new("Receiver", "Account", "receiver_password");
+        let miner = Account::new("Miner", "Account", "miner_password");
+        sender.add_money(10.0).unwrap();
+
+        let mut blockchain = BlockChain::new(1, DIFFICULTY);
+        blockchain.premine(vec![(sender.address(), PositiveF64::new(10.0).unwrap())]).unwrap();
+        blockchain.set_miner(&miner.address(), 50.0).unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 4.0, "sender_password").unwrap();
+        blockchain.push_transaction(&mut sender, &mut receiver, 1.0, "sender_password").unwrap();
+
+        let headers: Vec<[u8; 64]> = blockchain.iter().map(|b| b.hash()).collect();
+
+        assert_eq!(blockchain.balance_proof(&sender.address()).verify(&headers), Some(5.0)); // the premine is counted
+        assert_eq!(blockchain.balance_proof(&miner.address()).verify(&headers), Some(100.0));
+    }
+}
@@ -1,28 +1,40 @@
 use std::{fmt, error};
-use std::convert::{TryInto, TryFrom};
+use std::convert::TryInto;
 use sha2::{Sha512, Digest};
 use chrono::{DateTime, Utc};
 use crate::{
     account::Account,
-    positive_f64::PositiveF64
+    positive_f64::PositiveF64,
+    encoding::{Writer, Reader, to_hex},
 };
-use ed25519_dalek::{
-    Keypair,
-    Signature,
-    Signer,
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer};
+#[cfg(feature = "serde")]
+use {
+    serde::{Serialize, Deserialize},
+    chrono::SecondsFormat,
+    crate::encoding::from_hex,
 };
 
+/// The maximum length, in bytes, of the memo of a `Transaction`.
+pub const MAX_MEMO_LEN: usize = 256;
+
 /// A structure to handle the transactions of the blockchain.
 /// 
 /// Every transaction contains:
-/// - the sender's `Account`
-/// - the receiver's `Account`
+/// - the public part of the sender's `Account`, which is its public key and its nonce
+/// - the public part of the receiver's `Account`, which is its public key
 /// - the amount of the transaction
+/// - the fee paid by the sender to the miner of the block
 /// - the `DateTime<Utc>` time when the block was generated
-/// - the SHA-512 hash of the sender's password
 /// - the message to be signed
 /// - the digital signature of the message
 /// - the hash of the transaction
+/// - the `Condition` that must be met to spend the transaction, if any
+/// - the memo attached by the sender, if any
+/// - the hash of the transaction it reverses, if it's the reversal of a chargeback
+/// 
+/// The names, the balances, the secret keys and the hashes of the passwords of the accounts
+/// are never recorded, so the balance of the sender is known only by the chain.
 /// 
 /// Note: this structure **does not** handle the actual transfer of money,
 /// `.push_transaction()` from `blockchain::BlockChain` handles that.
@@ -31,11 +43,24 @@ pub struct Transaction {
     pub sender: Account,
     pub receiver: Account,
     amount: f64,
+    fee: PositiveF64,
     time: DateTime<Utc>,
-    hash_sender_password: [u8; 64],
     message: String,
     signature: [u8; 64],
     hash: [u8; 64],
+    condition: Option<Condition>,
+    memo: Option<String>,
+    reversal_of: Option<[u8; 64]>,
+}
+
+/// An enum to handle the simple conditions a `Transaction` can be subject to.
+/// 
+/// - `HeightAtLeast(height)` is met only if the transaction is spent in a block whose index is at least `height`.
+/// - `HashLock(hash)` is met only if a preimage whose SHA-512 hash is `hash` is provided when the transaction is spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    HeightAtLeast(usize),
+    HashLock([u8; 64]),
 }
 
 impl Transaction {
@@ -44,44 +69,137 @@ impl Transaction {
     /// In order to perform a new transaction, the sender must specify his account, his password,
     /// the amount to transfer and the receiver's account.
     /// 
+    /// The transaction is signed with the sender's keypair while it's generated,
+    /// so the function returns a `Result<Transaction, ValidationError>`, because
+    /// if the password doesn't match with the sender's one, a `ValidationError::WrongPassword` error is returned
+    /// and the transaction isn't signed at all.
+    /// 
     /// # Example
     /// ```
-    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::transaction::{Transaction, ValidationError};
     /// # use blockchain::account::Account;
     /// let mut alvin = Account::new("Alvin", "Wilton", "alvin_wilton_1990#");
     /// let egbert = Account::new("Egbert", "Tucker", "egby_tucky_PASS5000");
-    /// alvin.add_money(400.0);
+    /// alvin.add_money(400.0).unwrap();
     /// 
-    /// let transaction = Transaction::new(alvin, egbert, 30.0, "alvin_wilton_1990#");
+    /// let transaction = Transaction::new(alvin, egbert, 30.0, "alvin_wilton_1990#").unwrap();
     /// 
     /// assert_eq!(transaction.amount(), 30.0);
+    /// 
+    /// let egbert = Account::new("Egbert", "Tucker", "egby_tucky_PASS5000");
+    /// let alvin = Account::new("Alvin", "Wilton", "alvin_wilton_1990#");
+    /// 
+    /// assert_eq!(Transaction::new(egbert, alvin, 10.0, "wrong_password"), Err(ValidationError::WrongPassword));
     /// ```
-    pub fn new(sender: Account, receiver: Account, amount: f64, sender_password: &str) -> Self {
-        let mut hasher = Sha512::new();
+    pub fn new(sender: Account, receiver: Account, amount: f64, sender_password: &str) -> Result<Self, ValidationError> {
+        Transaction::build(TransactionBuilder::new(sender, receiver, amount, sender_password))
+    }
 
-        hasher.update(sender_password.as_bytes());
+    /// Generates a new `Transaction` with a memo, which is signed and hashed together with the other fields of the transaction,
+    /// so that it can't be changed without invalidating the transaction.
+    /// 
+    /// As in `Transaction::new()`, if the password doesn't match with the sender's one,
+    /// a `ValidationError::WrongPassword` error is returned, and if the memo is longer than `MAX_MEMO_LEN` bytes,
+    /// a `ValidationError::MemoTooLong` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{Transaction, ValidationError, MAX_MEMO_LEN};
+    /// # use blockchain::account::Account;
+    /// let mut graham = Account::new("Graham", "O'Brien", "graham_obrien_2018");
+    /// let grace = Account::new("Grace", "O'Brien", "grace_obrien_2018");
+    /// graham.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = Transaction::with_memo(graham.clone(), grace.clone(), 5.0, "Happy birthday!", "graham_obrien_2018").unwrap();
+    /// 
+    /// assert_eq!(transaction.memo(), Some("Happy birthday!"));
+    /// 
+    /// let long_memo = "a".repeat(MAX_MEMO_LEN + 1);
+    /// 
+    /// assert_eq!(Transaction::with_memo(graham, grace, 5.0, &long_memo, "graham_obrien_2018"), Err(ValidationError::MemoTooLong));
+    /// ```
+    pub fn with_memo(sender: Account, receiver: Account, amount: f64, memo: &str, sender_password: &str) -> Result<Self, ValidationError> {
+        Transaction::build(TransactionBuilder::new(sender, receiver, amount, sender_password).memo(memo))
+    }
 
-        let hash_sender_password = hasher
-            .finalize()[..]
-            .try_into()
-            .expect("Error generating the SHA-512 hash of the password.");
+    /// Generates a new `Transaction`, whose sender pays the fee to the miner of the block, on top of the amount;
+    /// `Transaction::new()` generates transactions with no fee.
+    /// 
+    /// As in `Transaction::new()`, if the password doesn't match with the sender's one,
+    /// a `ValidationError::WrongPassword` error is returned, and if the fee is negative,
+    /// a `ValidationError::InvalidAmount` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{Transaction, ValidationError};
+    /// # use blockchain::account::Account;
+    /// let mut amy = Account::new("Amy", "Pond", "The_Girl_Who_Waited");
+    /// let rory = Account::new("Rory", "Williams", "The_Last_Centurion");
+    /// amy.add_money(50.0).unwrap();
+    /// 
+    /// let transaction = Transaction::with_fee(amy.clone(), rory.clone(), 20.0, 0.5, "The_Girl_Who_Waited").unwrap();
+    /// 
+    /// assert_eq!(transaction.fee(), 0.5);
+    /// assert_eq!(Transaction::with_fee(amy, rory, 20.0, -0.5, "The_Girl_Who_Waited"), Err(ValidationError::InvalidAmount));
+    /// ```
+    pub fn with_fee(sender: Account, receiver: Account, amount: f64, fee: f64, sender_password: &str) -> Result<Self, ValidationError> {
+        Transaction::build(TransactionBuilder::new(sender, receiver, amount, sender_password).fee(fee))
+    }
+
+    /// Generates a new `Transaction`, which can be spent only if the `Condition` is met;
+    /// the condition is signed and hashed together with the other fields of the transaction.
+    /// 
+    /// As in `Transaction::new()`, if the password doesn't match with the sender's one,
+    /// a `ValidationError::WrongPassword` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{Transaction, Condition};
+    /// # use blockchain::account::Account;
+    /// let mut martha = Account::new("Martha", "Jones", "martha_jones_2007");
+    /// let tish = Account::new("Tish", "Jones", "tish_jones_2007");
+    /// martha.add_money(50.0).unwrap();
+    /// 
+    /// let transaction = Transaction::with_condition(martha, tish, 10.0, "martha_jones_2007", Condition::HeightAtLeast(5)).unwrap();
+    /// 
+    /// assert_eq!(transaction.condition(), Some(Condition::HeightAtLeast(5)));
+    /// ```
+    pub fn with_condition(sender: Account, receiver: Account, amount: f64, sender_password: &str, condition: Condition) -> Result<Self, ValidationError> {
+        Transaction::build(TransactionBuilder::new(sender, receiver, amount, sender_password).condition(condition))
+    }
+
+    /// Generates a new `Transaction` from the fields of the builder, checking the fee, the memo and the password,
+    /// but not the amount, which is checked by `.validate()`; only the public parts of the accounts are kept.
+    fn build(builder: TransactionBuilder) -> Result<Self, ValidationError> {
+        if builder.memo.as_ref().is_some_and(|memo| memo.len() > MAX_MEMO_LEN) {
+            return Err(ValidationError::MemoTooLong);
+        }
+
+        let fee = PositiveF64::new(builder.fee).map_err(|_| ValidationError::InvalidAmount)?;
+
+        if !builder.sender.verify_password(builder.sender_password) {
+            return Err(ValidationError::WrongPassword);
+        }
 
         let mut transaction = Self {
-            sender,
-            receiver,
-            amount,
-            time: Utc::now(),
-            hash_sender_password,
+            sender: builder.sender.public_part(),
+            receiver: Account::from_public_key(&builder.receiver.public_key(), 0),
+            amount: builder.amount,
+            fee,
+            time: builder.time.unwrap_or_else(Utc::now),
             message: String::new(),
             signature: [0; 64],
             hash: [0; 64],
+            condition: builder.condition,
+            memo: builder.memo,
+            reversal_of: None,
         };
 
-        transaction.sign();
+        transaction.sign(&builder.sender);
 
         transaction.calculate_hash();
 
-        transaction
+        Ok(transaction)
     }
 
     /// This method returns the amount of the transaction, since the `amount` field isn't `pub`.
@@ -92,9 +210,9 @@ impl Transaction {
     /// # use blockchain::account::Account;
     /// let mut chloe = Account::new("Chloe", "Savage", "KloeeSavageTrue1234");
     /// let zoey = Account::new("Zoey", "Jacobson", "ZoomingPassword000#");
-    /// chloe.add_money(300.0);
+    /// chloe.add_money(300.0).unwrap();
     /// 
-    /// let transaction = Transaction::new(chloe, zoey, 20.0, "KloeeSavageTrue1234");
+    /// let transaction = Transaction::new(chloe, zoey, 20.0, "KloeeSavageTrue1234").unwrap();
     /// 
     /// assert_eq!(transaction.amount(), 20.0);
     /// ```
@@ -102,6 +220,43 @@ impl Transaction {
         self.amount
     }
 
+    /// This method returns the fee of the transaction, since the `fee` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut chloe = Account::new("Chloe", "Savage", "KloeeSavageTrue1234");
+    /// let zoey = Account::new("Zoey", "Jacobson", "ZoomingPassword000#");
+    /// chloe.add_money(300.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(chloe, zoey, 20.0, "KloeeSavageTrue1234").unwrap();
+    /// 
+    /// assert_eq!(transaction.fee(), 0.0);
+    /// ```
+    pub fn fee(&self) -> f64 {
+        self.fee.value()
+    }
+
+    /// This method returns the nonce of the transaction, which is the nonce of the sender's `Account`
+    /// when the transaction was generated; it's signed together with the other fields of the transaction.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut bill = Account::new("Bill", "Potts", "bill_potts_2017");
+    /// let heather = Account::new("Heather", "Pilot", "heather_the_pilot");
+    /// bill.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(bill.clone(), heather, 5.0, "bill_potts_2017").unwrap();
+    /// 
+    /// assert_eq!(transaction.nonce(), bill.nonce());
+    /// ```
+    pub fn nonce(&self) -> u64 {
+        self.sender.nonce()
+    }
+
     /// This method returns the hash of the transaction, since the `hash` field isn't `pub`.
     /// 
     /// # Example
@@ -110,9 +265,9 @@ impl Transaction {
     /// # use blockchain::account::Account;
     /// let mut luke = Account::new("Luke", "Steffen", "Luke_Steffen999");
     /// let jerold = Account::new("Jerold", "Butcher", "Jer0ld_Butcher1000##");
-    /// luke.add_money(70.0);
+    /// luke.add_money(70.0).unwrap();
     /// 
-    /// let transaction = Transaction::new(luke, jerold, 10.0, "Luke_Steffen999");
+    /// let transaction = Transaction::new(luke, jerold, 10.0, "Luke_Steffen999").unwrap();
     /// 
     /// assert_eq!(transaction.hash().len(), 64);
     /// ```
@@ -120,23 +275,188 @@ impl Transaction {
         self.hash
     }
 
+    /// This method returns the hash of the transaction as a lowercase hex string,
+    /// which can be used to identify the transaction.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut ruby = Account::new("Ruby", "Sunday", "Ruby_Sunday_2023");
+    /// let carla = Account::new("Carla", "Sunday", "Carla_Sunday_2023");
+    /// ruby.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(ruby, carla, 5.0, "Ruby_Sunday_2023").unwrap();
+    /// 
+    /// assert_eq!(transaction.id().len(), 128);
+    /// assert!(transaction.id().starts_with(&transaction.short_id()));
+    /// ```
+    pub fn id(&self) -> String {
+        to_hex(&self.hash)
+    }
+
+    /// This method returns the first 16 characters of the id of the transaction,
+    /// which are enough to tell transactions apart in logs.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut ruby = Account::new("Ruby", "Sunday", "Ruby_Sunday_2023");
+    /// let carla = Account::new("Carla", "Sunday", "Carla_Sunday_2023");
+    /// ruby.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(ruby, carla, 5.0, "Ruby_Sunday_2023").unwrap();
+    /// 
+    /// assert_eq!(transaction.short_id().len(), 16);
+    /// ```
+    pub fn short_id(&self) -> String {
+        to_hex(&self.hash[..8])
+    }
+
+    /// This method returns the ed25519 signature of the transaction, since the `signature` field isn't `pub`;
+    /// the signature is generated with the sender's keypair when the transaction is generated.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut clara = Account::new("Clara", "Oswald", "Impossible_Girl_2013");
+    /// let danny = Account::new("Danny", "Pink", "danny_pink_2014");
+    /// clara.add_money(15.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(clara, danny, 5.0, "Impossible_Girl_2013").unwrap();
+    /// 
+    /// assert_ne!(transaction.signature(), [0; 64]);
+    /// ```
+    pub fn signature(&self) -> [u8; 64] {
+        self.signature
+    }
+
+    /// This method returns the time when the transaction was generated, as seconds since the Unix epoch;
+    /// the time is part of the signed message, so it can't be changed without invalidating the transaction.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// # use std::time::{SystemTime, UNIX_EPOCH};
+    /// let mut romana = Account::new("Romana", "Dvoratrelundar", "Romanadvoratrelundar");
+    /// let k9 = Account::new("K9", "Mark II", "Affirmative_Master");
+    /// romana.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(romana, k9, 5.0, "Romanadvoratrelundar").unwrap();
+    /// 
+    /// assert!(transaction.timestamp() <= SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+    /// ```
+    pub fn timestamp(&self) -> u64 {
+        self.time.timestamp() as u64
+    }
+
+    /// This method returns the memo of the transaction, if any, since the `memo` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut sarah = Account::new("Sarah", "Smith", "K9_is_a_good_dog");
+    /// let harry = Account::new("Harry", "Sullivan", "harry_sullivan_1974");
+    /// sarah.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(sarah, harry, 5.0, "K9_is_a_good_dog").unwrap();
+    /// 
+    /// assert_eq!(transaction.memo(), None);
+    /// ```
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+
+    /// This method returns the `Condition` of the transaction, since the `condition` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut sarah = Account::new("Sarah", "Smith", "K9_is_a_good_dog");
+    /// let harry = Account::new("Harry", "Sullivan", "harry_sullivan_1974");
+    /// sarah.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(sarah, harry, 5.0, "K9_is_a_good_dog").unwrap();
+    /// 
+    /// assert_eq!(transaction.condition(), None);
+    /// ```
+    pub fn condition(&self) -> Option<Condition> {
+        self.condition
+    }
+
+    /// This method returns the hash of the transaction reversed by this one, if it's the reversal
+    /// of a chargeback, since the `reversal_of` field isn't `pub`.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut sarah = Account::new("Sarah", "Smith", "K9_is_a_good_dog");
+    /// let harry = Account::new("Harry", "Sullivan", "harry_sullivan_1974");
+    /// sarah.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(sarah, harry, 5.0, "K9_is_a_good_dog").unwrap();
+    /// 
+    /// assert_eq!(transaction.reversal_of(), None);
+    /// ```
+    pub fn reversal_of(&self) -> Option<[u8; 64]> {
+        self.reversal_of
+    }
+
+    /// This method generates the transaction which reverses this one, sending the same amount
+    /// from the receiver back to the sender; the reversal isn't signed by the receiver, but by the admin
+    /// of the chain who authorized the chargeback, and it records the hash of this transaction.
+    pub(crate) fn reversed(&self, admin: &Keypair) -> Self {
+        let mut transaction = Self {
+            sender: self.receiver.clone(),
+            receiver: self.sender.clone(),
+            amount: self.amount,
+            fee: PositiveF64::zero(),
+            time: Utc::now(),
+            message: String::new(),
+            signature: [0; 64],
+            hash: [0; 64],
+            condition: None,
+            memo: None,
+            reversal_of: Some(self.hash),
+        };
+
+        transaction.message = transaction.build_message();
+        transaction.signature = admin.sign(transaction.message.as_bytes()).to_bytes();
+
+        transaction.calculate_hash();
+
+        transaction
+    }
+
     /// This method is called when a new transaction is generated,
     /// and it is used to perform the digital signature of the new transaction.
     /// 
     /// The digital signature is generated using the `Keypair` from the sender's account,
-    /// using the `ed25519_dalek` crate.
+    /// using the `ed25519_dalek` crate, since the transaction only records the public part of the account.
     /// 
     /// The signature is performed on the `message`, generated by using:
-    /// - the sender's `Account`
-    /// - the receiver's `Account`
-    /// - the amount of the transaction
+    /// - the address of the sender, and its nonce
+    /// - the address of the receiver
+    /// - the amount and the fee of the transaction
     /// - the `DateTime<Utc>` time when the block was generated
-    fn sign(&mut self) {
-        let keypair = Keypair::from_bytes(&self.sender.keypair()).expect("Error generating the Keypair while signing the transaction.");
-        
-        self.message = format!("{}{}{}{:?}", self.sender, self.receiver, self.amount, self.time);
+    /// - the `Condition` of the transaction
+    /// - the memo of the transaction
+    /// - the hash of the transaction it reverses, if any
+    fn sign(&mut self, sender: &Account) {
+        self.message = self.build_message();
+
+        self.signature = sender.sign(self.message.as_bytes()).to_bytes();
+    }
 
-        self.signature = keypair.sign(self.message.as_bytes()).to_bytes();
+    /// This method builds the message to be signed from the fields of the transaction.
+    fn build_message(&self) -> String {
+        format!("{}{}{}{}{}{:?}{:?}{:?}{:?}", self.sender.address(), self.sender.nonce(), self.receiver.address(), self.amount, self.fee, self.time, self.condition, self.memo, self.reversal_of)
     }
 
     /// This method is called when a new transacion is generated,
@@ -145,29 +465,135 @@ impl Transaction {
     /// The hash is calculated by using the `message` and the `signature`,
     /// both fields generated in the `sign()` method.
     fn calculate_hash(&mut self) {
+        self.hash = self.compute_hash();
+    }
+
+    /// This method computes the SHA-512 hash of the transaction from its fields,
+    /// rebuilding the message, so that any change to the transaction changes the hash.
+    pub(crate) fn compute_hash(&self) -> [u8; 64] {
         let mut hasher = Sha512::new();
 
-        let message = format!("{:?}{:?}", self.message, self.signature);
+        let message = format!("{:?}{:?}", self.build_message(), self.signature);
 
         hasher.update(message.as_bytes());
 
-        self.hash = hasher
+        hasher
             .finalize()[..]
             .try_into()
-            .expect("Error generating the SHA-512 hash of the transaction.");
+            .expect("Error generating the SHA-512 hash of the transaction.")
+    }
+
+    /// This method checks that the hash of the transaction matches with the hash recomputed from its fields,
+    /// so that a transaction received from someone else, for example deserialized, can be trusted.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::Transaction;
+    /// # use blockchain::account::Account;
+    /// let mut donna = Account::new("Donna", "Noble", "donna_noble_1978");
+    /// let wilf = Account::new("Wilfred", "Mott", "wilf_mott_1930");
+    /// donna.add_money(10.0).unwrap();
+    /// 
+    /// let transaction = Transaction::new(donna, wilf, 5.0, "donna_noble_1978").unwrap();
+    /// 
+    /// assert!(transaction.verify_integrity());
+    /// ```
+    pub fn verify_integrity(&self) -> bool {
+        self.compute_hash() == self.hash
+    }
+
+    /// This method writes the fields of the transaction as bytes;
+    /// only the public keys of the accounts, and the nonce of the sender, are written.
+    pub(crate) fn encode(&self, writer: &mut Writer) {
+        writer.array(self.sender.public_key().as_bytes());
+        writer.u64(self.sender.nonce());
+        writer.array(self.receiver.public_key().as_bytes());
+        writer.f64(self.amount);
+        writer.f64(self.fee.value());
+        writer.time(self.time);
+        writer.string(&self.message);
+        writer.array(&self.signature);
+        writer.array(&self.hash);
+
+        match self.condition {
+            None => writer.u64(0),
+            Some(Condition::HeightAtLeast(height)) => {
+                writer.u64(1);
+                writer.u64(height as u64);
+            },
+            Some(Condition::HashLock(hash)) => {
+                writer.u64(2);
+                writer.array(&hash);
+            },
+        }
+
+        match &self.memo {
+            None => writer.u64(0),
+            Some(memo) => {
+                writer.u64(1);
+                writer.string(memo);
+            },
+        }
+
+        match &self.reversal_of {
+            None => writer.u64(0),
+            Some(hash) => {
+                writer.u64(1);
+                writer.array(hash);
+            },
+        }
+    }
+
+    /// This method reads back a transaction written by `encode()`,
+    /// and returns `None` if the bytes don't represent a transaction.
+    pub(crate) fn decode(reader: &mut Reader) -> Option<Self> {
+        let sender = PublicKey::from_bytes(&reader.array::<32>()?).ok()?;
+        let nonce = reader.u64()?;
+        let receiver = PublicKey::from_bytes(&reader.array::<32>()?).ok()?;
+
+        Some(Self {
+            sender: Account::from_public_key(&sender, nonce),
+            receiver: Account::from_public_key(&receiver, 0),
+            amount: reader.f64()?,
+            fee: PositiveF64::new(reader.f64()?).ok()?,
+            time: reader.time()?,
+            message: reader.string()?,
+            signature: reader.array()?,
+            hash: reader.array()?,
+            condition: match reader.u64()? {
+                0 => None,
+                1 => Some(Condition::HeightAtLeast(reader.u64()?.try_into().ok()?)),
+                2 => Some(Condition::HashLock(reader.array()?)),
+                _ => return None,
+            },
+            memo: match reader.u64()? {
+                0 => None,
+                1 => Some(reader.string()?),
+                _ => return None,
+            },
+            reversal_of: match reader.u64()? {
+                0 => None,
+                1 => Some(reader.array()?),
+                _ => return None,
+            },
+        })
     }
 
     /// This method checks if the transaction is valid,
     /// and returns a `Err(ValidationError)` if the transaction isn't valid.
     /// 
-    /// - If the hash in the input doesn't match with the `hash` of the transaction,
-    /// a `ValidationError::Tempered` error is returned.
-    /// - If the hash of the sender's password doesn't match with the `hash_sender_password` field,
-    /// a `ValidationError::WrongPassword` error is returned.
-    /// - If the signature verification doesn't succeed,
-    /// a `ValidationError::InvalidSign` error is returned.
-    /// - If the amount is zero or negative, or if the amount of the transaction is more than the sender's balance,
-    /// a `ValidationError::InvalidAmount` error is returned.
+    /// - If the hash in the input doesn't match with the hash of the transaction, recomputed from its fields,
+    ///   a `ValidationError::Tempered` error is returned.
+    /// - If the transaction is the reversal of a chargeback, which is signed by the admin of the chain
+    ///   instead of the sender, a `ValidationError::InvalidSignature` error is returned: reversals are checked
+    ///   by the `BlockChain`, which knows the public key of the admin.
+    /// - If the signature of the message, rebuilt from the fields of the transaction,
+    ///   can't be verified with the sender's public key, a `ValidationError::InvalidSignature` error is returned.
+    /// - If the amount is zero, negative or not finite, a `ValidationError::InvalidAmount` error is returned.
+    /// - If the memo is longer than `MAX_MEMO_LEN` bytes, a `ValidationError::MemoTooLong` error is returned.
+    /// 
+    /// The balance of the sender isn't checked, since the transaction doesn't record it:
+    /// the `BlockChain` checks that the sender can afford the transaction.
     /// 
     /// # Example
     /// ```
@@ -175,36 +601,388 @@ impl Transaction {
     /// # use blockchain::account::Account;
     /// let mut odin = Account::new("Odin", "Dennell", "OdinDennellPass102938");
     /// let kenya = Account::new("Kenya", "Dawson", "kenyyyya_dawwwwson69");
-    /// odin.add_money(10.0);
+    /// odin.add_money(10.0).unwrap();
     /// 
     /// // cloning accounts because `Account` doesn't implement the `Copy` trait
-    /// let valid_transaction = Transaction::new(odin.clone(), kenya.clone(), 5.0, "OdinDennellPass102938");
+    /// let valid_transaction = Transaction::new(odin.clone(), kenya.clone(), 5.0, "OdinDennellPass102938").unwrap();
     /// 
     /// assert_eq!(valid_transaction.validate(valid_transaction.hash()).unwrap(), ()); // if the transaction is valid `()` is returned
     /// 
     /// assert_eq!(kenya.balance(), 0.0); // as you can see Kenya still has no money (check the definition of the struct for reference)
     /// 
-    /// let invalid_transaction = Transaction::new(kenya, odin, 80.0, "kenyyyya_dawwwwson69");
+    /// let invalid_transaction = Transaction::new(kenya, odin, 0.0, "kenyyyya_dawwwwson69").unwrap();
     /// 
     /// assert_eq!(invalid_transaction.validate(invalid_transaction.hash()), Err(ValidationError::InvalidAmount));
     /// ```
     pub fn validate(&self, hash: [u8; 64]) -> Result<(), ValidationError> {
-        let signature = Signature::try_from(self.signature).expect("Error generating the Signature while validating the transaction.");
+        let signature = Signature::from(self.signature);
+
+        if hash != self.compute_hash() {
+            Err(ValidationError::Tempered)
+        } else if self.reversal_of.is_some() || !Account::verify(&self.sender.public_key(), self.build_message().as_bytes(), &signature) {
+            Err(ValidationError::InvalidSignature)
+        } else if PositiveF64::new(self.amount).is_err() || self.amount == 0.0 {
+            Err(ValidationError::InvalidAmount)
+        } else if self.memo.as_ref().is_some_and(|memo| memo.len() > MAX_MEMO_LEN) {
+            Err(ValidationError::MemoTooLong)
+        } else {
+            Ok(())
+        }
+    }
 
-        let keypair = Keypair::from_bytes(&self.sender.keypair()).expect("Error generating the Keypair while validating the transaction.");
+    /// This method checks if the transaction is a valid reversal of a chargeback, signed by the admin
+    /// whose public key is given; as in `.validate()`, the balance of the sender isn't checked.
+    pub(crate) fn validate_reversal(&self, hash: [u8; 64], admin: &PublicKey) -> Result<(), ValidationError> {
+        let signature = Signature::from(self.signature);
 
-        if hash != self.hash {
+        if hash != self.compute_hash() {
             Err(ValidationError::Tempered)
-        } else if self.hash_sender_password != self.sender.hash_password() {
-            Err(ValidationError::WrongPassword)
-        } else if keypair.verify(self.message.as_bytes(), &signature).is_err() {
+        } else if self.reversal_of.is_none() || !Account::verify(admin, self.build_message().as_bytes(), &signature) {
             Err(ValidationError::InvalidSignature)
-        } else if PositiveF64::new(self.amount).is_err() || self.amount == 0.0 || self.amount > self.sender.balance() {
+        } else if PositiveF64::new(self.amount).is_err() || self.amount == 0.0 {
             Err(ValidationError::InvalidAmount)
         } else {
             Ok(())
         }
     }
+
+    /// This method checks if the transaction is valid, as in `.validate()`,
+    /// and if its `Condition` is met when it's spent in the block at the given height,
+    /// using the preimage for a `Condition::HashLock`.
+    /// 
+    /// If the condition isn't met, a `ValidationError::ConditionNotMet` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{Transaction, Condition, ValidationError};
+    /// # use blockchain::account::Account;
+    /// # use sha2::{Sha512, Digest};
+    /// # use std::convert::TryInto;
+    /// let mut jack = Account::new("Jack", "Harkness", "Torchwood_Captain");
+    /// let ianto = Account::new("Ianto", "Jones", "Torchwood_Coffee");
+    /// jack.add_money(40.0).unwrap();
+    /// 
+    /// let height_locked = Transaction::with_condition(jack.clone(), ianto.clone(), 10.0, "Torchwood_Captain", Condition::HeightAtLeast(3)).unwrap();
+    /// 
+    /// assert_eq!(height_locked.validate_conditional(height_locked.hash(), 2, None), Err(ValidationError::ConditionNotMet));
+    /// assert_eq!(height_locked.validate_conditional(height_locked.hash(), 3, None), Ok(()));
+    /// 
+    /// let lock: [u8; 64] = Sha512::digest(b"secret")[..].try_into().unwrap();
+    /// let hash_locked = Transaction::with_condition(jack, ianto, 10.0, "Torchwood_Captain", Condition::HashLock(lock)).unwrap();
+    /// 
+    /// assert_eq!(hash_locked.validate_conditional(hash_locked.hash(), 0, None), Err(ValidationError::ConditionNotMet));
+    /// assert_eq!(hash_locked.validate_conditional(hash_locked.hash(), 0, Some(b"wrong")), Err(ValidationError::ConditionNotMet));
+    /// assert_eq!(hash_locked.validate_conditional(hash_locked.hash(), 0, Some(b"secret")), Ok(()));
+    /// ```
+    pub fn validate_conditional(&self, hash: [u8; 64], height: usize, preimage: Option<&[u8]>) -> Result<(), ValidationError> {
+        self.validate(hash)?;
+
+        let is_met = match self.condition {
+            None => true,
+            Some(Condition::HeightAtLeast(min_height)) => height >= min_height,
+            Some(Condition::HashLock(lock)) => preimage.is_some_and(|p| Sha512::digest(p)[..] == lock[..]),
+        };
+
+        if is_met {
+            Ok(())
+        } else {
+            Err(ValidationError::ConditionNotMet)
+        }
+    }
+}
+
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}: {} (fee {}) @ {} [{}]",
+            self.sender.address(),
+            self.receiver.address(),
+            self.amount,
+            self.fee,
+            self.timestamp(),
+            self.short_id(),
+        )
+    }
+}
+
+/// A structure to generate a `Transaction` step by step: the sender's account, the receiver's account,
+/// the amount and the sender's password are required, while the other fields are optional.
+/// 
+/// Unlike `Transaction::new()`, `.build()` also checks that the amount is positive.
+#[derive(Clone)]
+pub struct TransactionBuilder<'a> {
+    sender: Account,
+    receiver: Account,
+    amount: f64,
+    sender_password: &'a str,
+    fee: f64,
+    memo: Option<String>,
+    time: Option<DateTime<Utc>>,
+    condition: Option<Condition>,
+}
+
+impl<'a> TransactionBuilder<'a> {
+    /// Generates a new `TransactionBuilder`, with no fee, no memo, no `Condition`,
+    /// and the time when the transaction is built.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::TransactionBuilder;
+    /// # use blockchain::account::Account;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = TransactionBuilder::new(yaz, ryan, 5.0, "yaz_khan_2018").build().unwrap();
+    /// 
+    /// assert_eq!(transaction.amount(), 5.0);
+    /// assert_eq!(transaction.fee(), 0.0);
+    /// ```
+    pub fn new(sender: Account, receiver: Account, amount: f64, sender_password: &'a str) -> Self {
+        Self {
+            sender,
+            receiver,
+            amount,
+            sender_password,
+            fee: 0.0,
+            memo: None,
+            time: None,
+            condition: None,
+        }
+    }
+
+    /// This method sets the fee the sender pays to the miner of the block, on top of the amount.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::TransactionBuilder;
+    /// # use blockchain::account::Account;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = TransactionBuilder::new(yaz, ryan, 5.0, "yaz_khan_2018").fee(0.1).build().unwrap();
+    /// 
+    /// assert_eq!(transaction.fee(), 0.1);
+    /// ```
+    pub fn fee(mut self, fee: f64) -> Self {
+        self.fee = fee;
+
+        self
+    }
+
+    /// This method sets the memo of the transaction.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::TransactionBuilder;
+    /// # use blockchain::account::Account;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = TransactionBuilder::new(yaz, ryan, 5.0, "yaz_khan_2018").memo("Lunch").build().unwrap();
+    /// 
+    /// assert_eq!(transaction.memo(), Some("Lunch"));
+    /// ```
+    pub fn memo(mut self, memo: &str) -> Self {
+        self.memo = Some(String::from(memo));
+
+        self
+    }
+
+    /// This method sets the time of the transaction, instead of the time when the transaction is built.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::TransactionBuilder;
+    /// # use blockchain::account::Account;
+    /// # use chrono::{TimeZone, Utc};
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let time = Utc.timestamp_opt(1_540_000_000, 0).unwrap();
+    /// let transaction = TransactionBuilder::new(yaz, ryan, 5.0, "yaz_khan_2018").timestamp(time).build().unwrap();
+    /// 
+    /// assert_eq!(transaction.timestamp(), 1_540_000_000);
+    /// ```
+    pub fn timestamp(mut self, time: DateTime<Utc>) -> Self {
+        self.time = Some(time);
+
+        self
+    }
+
+    /// This method sets the `Condition` that must be met to spend the transaction.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{TransactionBuilder, Condition};
+    /// # use blockchain::account::Account;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let transaction = TransactionBuilder::new(yaz, ryan, 5.0, "yaz_khan_2018").condition(Condition::HeightAtLeast(3)).build().unwrap();
+    /// 
+    /// assert_eq!(transaction.condition(), Some(Condition::HeightAtLeast(3)));
+    /// ```
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+
+        self
+    }
+
+    /// This method generates the `Transaction`, signing it with the sender's keypair.
+    /// 
+    /// The function returns a `Result<Transaction, ValidationError>`, because
+    /// if the amount is zero, negative or not finite, or the fee is negative, a `ValidationError::InvalidAmount` error is returned,
+    /// if the memo is longer than `MAX_MEMO_LEN` bytes, a `ValidationError::MemoTooLong` error is returned,
+    /// and if the password doesn't match with the sender's one, a `ValidationError::WrongPassword` error is returned.
+    /// 
+    /// # Example
+    /// ```
+    /// # use blockchain::transaction::{TransactionBuilder, ValidationError};
+    /// # use blockchain::account::Account;
+    /// let mut yaz = Account::new("Yasmin", "Khan", "yaz_khan_2018");
+    /// let ryan = Account::new("Ryan", "Sinclair", "ryan_sinclair_2018");
+    /// yaz.add_money(20.0).unwrap();
+    /// 
+    /// let builder = TransactionBuilder::new(yaz.clone(), ryan.clone(), 0.0, "yaz_khan_2018");
+    /// 
+    /// assert_eq!(builder.build(), Err(ValidationError::InvalidAmount));
+    /// 
+    /// let builder = TransactionBuilder::new(yaz, ryan, 5.0, "wrong_password");
+    /// 
+    /// assert_eq!(builder.build(), Err(ValidationError::WrongPassword));
+    /// ```
+    pub fn build(self) -> Result<Transaction, ValidationError> {
+        if !self.amount.is_finite() || self.amount <= 0.0 {
+            return Err(ValidationError::InvalidAmount);
+        }
+
+        Transaction::build(self)
+    }
+}
+
+/// The representation of a `Transaction` used by `serde`:
+/// the accounts are written as their addresses and public keys, the byte arrays as hex strings,
+/// and the time as an RFC 3339 string, so that no precision is lost.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerdeTransaction {
+    sender: SerdeParty,
+    receiver: SerdeParty,
+    nonce: u64,
+    amount: f64,
+    fee: PositiveF64,
+    timestamp: String,
+    message: String,
+    signature: String,
+    hash: String,
+    condition: Option<SerdeCondition>,
+    memo: Option<String>,
+    reversal_of: Option<String>,
+}
+
+/// The representation of a party of a `Transaction` used by `serde`, which is public:
+/// the address and the hex of the public key of the account.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerdeParty {
+    address: String,
+    public_key: String,
+}
+
+/// The representation of a `Condition` used by `serde`.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+enum SerdeCondition {
+    HeightAtLeast(usize),
+    HashLock(String),
+}
+
+/// A `Transaction` is serialized with all its fields; the hash is serialized as it is,
+/// so it must be checked with `.verify_integrity()` after deserializing the transaction.
+#[cfg(feature = "serde")]
+impl Serialize for Transaction {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let party = |account: &Account| SerdeParty {
+            address: account.address(),
+            public_key: to_hex(account.public_key().as_bytes()),
+        };
+
+        SerdeTransaction {
+            sender: party(&self.sender),
+            receiver: party(&self.receiver),
+            nonce: self.sender.nonce(),
+            amount: self.amount,
+            fee: self.fee,
+            timestamp: self.time.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            message: self.message.clone(),
+            signature: to_hex(&self.signature),
+            hash: to_hex(&self.hash),
+            condition: self.condition.map(|condition| match condition {
+                Condition::HeightAtLeast(height) => SerdeCondition::HeightAtLeast(height),
+                Condition::HashLock(lock) => SerdeCondition::HashLock(to_hex(&lock)),
+            }),
+            memo: self.memo.clone(),
+            reversal_of: self.reversal_of.map(|hash| to_hex(&hash)),
+        }.serialize(serializer)
+    }
+}
+
+/// A `Transaction` is deserialized without checking its hash, nor its signature:
+/// `.verify_integrity()` and `.validate()` must be used to check them;
+/// the address of every account must match with its public key.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let transaction = SerdeTransaction::deserialize(deserializer)?;
+
+        let account = |party: &SerdeParty, nonce: u64| {
+            let account = from_hex(&party.public_key)
+                .and_then(|bytes| PublicKey::from_bytes(&bytes).ok())
+                .map(|public_key| Account::from_public_key(&public_key, nonce))
+                .ok_or_else(|| D::Error::custom("invalid public key"))?;
+
+            if account.address() == party.address {
+                Ok(account)
+            } else {
+                Err(D::Error::custom("the address doesn't match with the public key"))
+            }
+        };
+
+        let array = |hex: &str| {
+            from_hex(hex)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| D::Error::custom("invalid hex array"))
+        };
+
+        Ok(Self {
+            sender: account(&transaction.sender, transaction.nonce)?,
+            receiver: account(&transaction.receiver, 0)?,
+            amount: transaction.amount,
+            fee: transaction.fee,
+            time: DateTime::parse_from_rfc3339(&transaction.timestamp)
+                .map_err(D::Error::custom)?
+                .with_timezone(&Utc),
+            message: transaction.message,
+            signature: array(&transaction.signature)?,
+            hash: array(&transaction.hash)?,
+            condition: match transaction.condition {
+                None => None,
+                Some(SerdeCondition::HeightAtLeast(height)) => Some(Condition::HeightAtLeast(height)),
+                Some(SerdeCondition::HashLock(lock)) => Some(Condition::HashLock(array(&lock)?)),
+            },
+            memo: transaction.memo,
+            reversal_of: match transaction.reversal_of {
+                None => None,
+                Some(hash) => Some(array(&hash)?),
+            },
+        })
+    }
 }
 
 /// An enum to handle errors generated while validating `Transaction`s.
@@ -214,6 +992,9 @@ pub enum ValidationError {
     WrongPassword,
     InvalidSignature,
     InvalidAmount,
+    ConditionNotMet,
+    MemoTooLong,
+    StaleNonce,
 }
 
 impl fmt::Display for ValidationError {
@@ -222,9 +1003,307 @@ impl fmt::Display for ValidationError {
             Self::Tempered =>  write!(f, "Tempered transaction."),
             Self::WrongPassword => write!(f, "Wrong password."),
             Self::InvalidSignature => write!(f, "Invalid signature."),
-            Self::InvalidAmount => write!(f, "Invalid amount.")
+            Self::InvalidAmount => write!(f, "Invalid amount."),
+            Self::ConditionNotMet => write!(f, "Condition not met."),
+            Self::MemoTooLong => write!(f, "Memo too long."),
+            Self::StaleNonce => write!(f, "Stale nonce, the transaction may be a replay."),
         }
     }
 }
 
 impl error::Error for ValidationError {}
+
+#[cfg(test)]
+impl Transaction {
+    /// This method changes the amount without recomputing the hash, as an attacker would.
+    pub(crate) fn tamper_amount(&mut self, amount: f64) {
+        self.amount = amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn accounts() -> (Account, Account) {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        (sender, receiver)
+    }
+
+    #[test]
+    fn valid_transaction_is_validated() {
+        let (sender, receiver) = accounts();
+        let transaction = Transaction::new(sender, receiver, 5.0, "sender_password").unwrap();
+
+        assert_eq!(transaction.validate(transaction.hash()), Ok(()));
+    }
+
+    #[test]
+    fn tampered_transaction_is_rejected() {
+        let (sender, receiver) = accounts();
+        let mut transaction = Transaction::new(sender, receiver, 5.0, "sender_password").unwrap();
+        let hash = transaction.hash();
+
+        assert_eq!(transaction.validate([0; 64]), Err(ValidationError::Tempered));
+
+        transaction.amount = 1.0;
+
+        assert_eq!(transaction.validate(hash), Err(ValidationError::Tempered));
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let (sender, receiver) = accounts();
+
+        assert_eq!(Transaction::new(sender.clone(), receiver.clone(), 5.0, "wrong_password"), Err(ValidationError::WrongPassword));
+        assert_eq!(TransactionBuilder::new(sender, receiver, 5.0, "wrong_password").build(), Err(ValidationError::WrongPassword));
+    }
+
+    #[test]
+    fn only_the_public_parts_of_the_accounts_are_recorded() {
+        let (mut sender, receiver) = accounts();
+        sender.increment_nonce();
+
+        let transaction = Transaction::new(sender.clone(), receiver.clone(), 5.0, "sender_password").unwrap();
+
+        for (recorded, account) in &[(&transaction.sender, &sender), (&transaction.receiver, &receiver)] {
+            assert_eq!(recorded.address(), account.address());
+            assert_eq!(recorded.public_key(), account.public_key());
+            assert_eq!(recorded.to_string(), "( : 0)");
+            assert_eq!(recorded.keypair()[..32], [0; 32]);
+            assert_eq!(recorded.hash_password(), [0; 64]);
+        }
+
+        assert_eq!(transaction.nonce(), 1);
+
+        let mut writer = Writer::default();
+        transaction.encode(&mut writer);
+        let bytes = writer.bytes();
+
+        assert!(!bytes.windows(32).any(|window| window == &sender.keypair()[..32]));
+        assert!(!bytes.windows(64).any(|window| window == sender.hash_password()));
+        assert_eq!(Transaction::decode(&mut Reader::new(&bytes)), Some(transaction));
+    }
+
+    #[test]
+    fn signature_verifies_against_the_sender_public_key() {
+        let (sender, receiver) = accounts();
+        let transaction = Transaction::new(sender.clone(), receiver.clone(), 5.0, "sender_password").unwrap();
+        let signature = Signature::from(transaction.signature());
+
+        assert!(Account::verify(&sender.public_key(), transaction.build_message().as_bytes(), &signature));
+        assert!(!Account::verify(&receiver.public_key(), transaction.build_message().as_bytes(), &signature));
+    }
+
+    #[test]
+    fn invalid_signature_is_rejected() {
+        let (sender, receiver) = accounts();
+        let mut transaction = Transaction::new(sender, receiver.clone(), 5.0, "sender_password").unwrap();
+
+        transaction.signature = receiver.sign(transaction.build_message().as_bytes()).to_bytes();
+        transaction.calculate_hash();
+
+        assert_eq!(transaction.validate(transaction.hash()), Err(ValidationError::InvalidSignature));
+    }
+
+    #[test]
+    fn invalid_amount_is_rejected() {
+        let (sender, receiver) = accounts();
+
+        for amount in &[0.0, -5.0, f64::NAN] {
+            let transaction = Transaction::new(sender.clone(), receiver.clone(), *amount, "sender_password").unwrap();
+
+            assert_eq!(transaction.validate(transaction.hash()), Err(ValidationError::InvalidAmount));
+        }
+    }
+
+    #[test]
+    fn memo_is_part_of_the_hash() {
+        let (sender, receiver) = accounts();
+
+        let mut first = Transaction::with_memo(sender.clone(), receiver.clone(), 5.0, "first memo", "sender_password").unwrap();
+        let mut second = first.clone();
+
+        second.memo = Some(String::from("second memo"));
+        second.sign(&sender);
+        second.calculate_hash();
+
+        assert_ne!(first.hash(), second.hash());
+
+        let hash = first.hash();
+        first.memo = Some(String::from("altered memo"));
+
+        assert_eq!(first.validate(hash), Err(ValidationError::Tempered));
+        assert_eq!(
+            Transaction::with_memo(sender, receiver, 5.0, &"a".repeat(MAX_MEMO_LEN + 1), "sender_password"),
+            Err(ValidationError::MemoTooLong),
+        );
+    }
+
+    #[test]
+    fn builder_with_required_fields_only() {
+        let (sender, receiver) = accounts();
+
+        let transaction = TransactionBuilder::new(sender, receiver, 5.0, "sender_password").build().unwrap();
+
+        assert_eq!(transaction.fee(), 0.0);
+        assert_eq!(transaction.memo(), None);
+        assert_eq!(transaction.condition(), None);
+        assert_eq!(transaction.validate(transaction.hash()), Ok(()));
+    }
+
+    #[test]
+    fn builder_with_every_field() {
+        let (sender, receiver) = accounts();
+        let time = Utc::now() - chrono::Duration::hours(1);
+
+        let transaction = TransactionBuilder::new(sender.clone(), receiver.clone(), 5.0, "sender_password")
+            .fee(0.5)
+            .memo("rent")
+            .timestamp(time)
+            .condition(Condition::HeightAtLeast(2))
+            .build()
+            .unwrap();
+
+        assert_eq!(transaction.amount(), 5.0);
+        assert_eq!(transaction.fee(), 0.5);
+        assert_eq!(transaction.memo(), Some("rent"));
+        assert_eq!(transaction.timestamp(), time.timestamp() as u64);
+        assert_eq!(transaction.condition(), Some(Condition::HeightAtLeast(2)));
+        assert_eq!(transaction.validate_conditional(transaction.hash(), 2, None), Ok(()));
+
+        let builder = TransactionBuilder::new(sender, receiver, 5.0, "sender_password");
+
+        assert_eq!(builder.clone().fee(-1.0).build(), Err(ValidationError::InvalidAmount));
+        assert_eq!(builder.memo(&"a".repeat(MAX_MEMO_LEN + 1)).build(), Err(ValidationError::MemoTooLong));
+    }
+
+    #[test]
+    fn transactions_at_different_times_have_different_hashes() {
+        let (sender, receiver) = accounts();
+
+        let time = Utc::now();
+
+        let first = TransactionBuilder::new(sender.clone(), receiver.clone(), 5.0, "sender_password")
+            .timestamp(time)
+            .build()
+            .unwrap();
+        let second = TransactionBuilder::new(sender, receiver, 5.0, "sender_password")
+            .timestamp(time + chrono::Duration::seconds(1))
+            .build()
+            .unwrap();
+
+        assert!(second.timestamp() > first.timestamp());
+        assert_ne!(first.hash(), second.hash());
+    }
+
+    #[test]
+    fn id_is_the_hex_of_the_hash() {
+        let (sender, receiver) = accounts();
+        let transaction = Transaction::new(sender, receiver, 5.0, "sender_password").unwrap();
+
+        assert_eq!(transaction.id().len(), 128);
+        assert_eq!(transaction.id(), transaction.clone().id());
+        assert!(transaction.id().chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        assert_eq!(transaction.short_id(), transaction.id()[..16]);
+    }
+
+    #[test]
+    fn display_shows_participants_and_amount() {
+        let (sender, receiver) = accounts();
+        let transaction = Transaction::with_fee(sender.clone(), receiver.clone(), 5.0, 0.25, "sender_password").unwrap();
+
+        let displayed = transaction.to_string();
+
+        assert!(displayed.starts_with(&format!("{} -> {}: 5 (fee 0.25)", sender.address(), receiver.address())));
+        assert!(displayed.ends_with(&format!("[{}]", transaction.short_id())));
+    }
+
+    #[test]
+    fn validation_errors_display_distinct_messages() {
+        let errors = [
+            ValidationError::Tempered,
+            ValidationError::WrongPassword,
+            ValidationError::InvalidSignature,
+            ValidationError::InvalidAmount,
+            ValidationError::ConditionNotMet,
+            ValidationError::MemoTooLong,
+            ValidationError::StaleNonce,
+        ];
+
+        let messages: HashSet<String> = errors.iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(messages.len(), errors.len());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn transaction() -> Transaction {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let lock: [u8; 64] = Sha512::digest(b"secret")[..].try_into().unwrap();
+
+        Transaction::with_condition(sender, receiver, 5.0, "sender_password", Condition::HashLock(lock)).unwrap()
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let transaction = transaction();
+
+        let json = serde_json::to_string(&transaction).unwrap();
+        let restored: Transaction = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, transaction);
+        assert!(restored.verify_integrity());
+        assert_eq!(restored.validate(restored.hash()), Ok(()));
+    }
+
+    #[test]
+    fn edited_amount_fails_integrity() {
+        let transaction = transaction();
+
+        let mut json: serde_json::Value = serde_json::to_value(&transaction).unwrap();
+        json["amount"] = serde_json::json!(9.0);
+
+        let edited: Transaction = serde_json::from_value(json).unwrap();
+
+        assert_eq!(edited.amount(), 9.0);
+        assert!(!edited.verify_integrity());
+    }
+
+    #[test]
+    fn json_contains_only_public_keys_and_addresses() {
+        let mut sender = Account::new("Sender", "Account", "sender_password");
+        let receiver = Account::new("Receiver", "Account", "receiver_password");
+        sender.add_money(10.0).unwrap();
+
+        let transaction = Transaction::new(sender.clone(), receiver.clone(), 5.0, "sender_password").unwrap();
+        let json = serde_json::to_value(&transaction).unwrap();
+
+        for (party, account) in &[("sender", &sender), ("receiver", &receiver)] {
+            assert_eq!(json[party], serde_json::json!({
+                "address": account.address(),
+                "public_key": to_hex(account.public_key().as_bytes()),
+            }));
+
+            let text = json.to_string();
+
+            assert!(!text.contains(&to_hex(&account.keypair()[..32])));
+            assert!(!text.contains(&to_hex(&account.hash_password())));
+        }
+
+        let mut forged = json.clone();
+        forged["sender"]["address"] = serde_json::json!(receiver.address());
+
+        assert!(serde_json::from_value::<Transaction>(forged).is_err());
+    }
+}